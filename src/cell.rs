@@ -1,4 +1,4 @@
-use crate::lib::CellType;
+use crate::lib::{CellType, MeshError};
 
 
 #[derive(Debug, PartialEq, Clone)]
@@ -13,14 +13,19 @@ impl MeshCell {
         self.co.clone()
     }
 
-    pub fn new(cell_type: CellType, connectivity: &Vec<usize>) -> Result<MeshCell, &'static str>{
+    pub fn new(cell_type: CellType, connectivity: &[usize]) -> Result<MeshCell, MeshError>{
 
         if connectivity.len() != cell_type.get_nb_of_connectivities() {
-            return Err("connectivity not implemented for this cell_type");
+            return Err(MeshError::ConnectivitySize {
+                expected: cell_type.get_nb_of_connectivities(),
+                actual: connectivity.len(),
+                cell_type,
+                cell_name: None,
+            });
         }
         Ok(MeshCell {
             ty: cell_type,
-            co: connectivity.clone(),
+            co: connectivity.to_vec(),
         })
     }
 }
@@ -0,0 +1,427 @@
+use crate::mesh_enums::CellType;
+
+// Topology of the Code_aster reference elements (see R3.01.01).
+// Node indices are local, i.e. positions in `MeshCell::co`. Corner nodes come first,
+// then the mid-edge nodes, then the face and volume centers.
+// Faces are ordered so that their normal points outwards (counterclockwise seen from
+// outside), quadratic edges and faces list their corners first, then their mid nodes
+// in the order of their edges, then their center.
+
+// local node indices of an edge or a face of a cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct LocalEntity {
+    pub ty: CellType,
+    pub nodes: &'static [usize],
+}
+
+const SEG_EDGES: [&[usize]; 1] = [&[0, 1, 2, 3]];
+
+const TRIA_EDGES: [&[usize]; 3] = [&[0, 1, 3], &[1, 2, 4], &[2, 0, 5]];
+const TRIA_FACES: [&[usize]; 1] = [&[0, 1, 2, 3, 4, 5, 6]];
+
+const QUAD_EDGES: [&[usize]; 4] = [&[0, 1, 4], &[1, 2, 5], &[2, 3, 6], &[3, 0, 7]];
+const QUAD_FACES: [&[usize]; 1] = [&[0, 1, 2, 3, 4, 5, 6, 7, 8]];
+
+const HEXA_EDGES: [&[usize]; 12] = [
+    &[0, 1, 8],
+    &[1, 2, 9],
+    &[2, 3, 10],
+    &[3, 0, 11],
+    &[0, 4, 12],
+    &[1, 5, 13],
+    &[2, 6, 14],
+    &[3, 7, 15],
+    &[4, 5, 16],
+    &[5, 6, 17],
+    &[6, 7, 18],
+    &[7, 4, 19],
+];
+const HEXA_FACES: [&[usize]; 6] = [
+    &[0, 3, 2, 1, 11, 10, 9, 8, 20],
+    &[0, 1, 5, 4, 8, 13, 16, 12, 21],
+    &[1, 2, 6, 5, 9, 14, 17, 13, 22],
+    &[2, 3, 7, 6, 10, 15, 18, 14, 23],
+    &[0, 4, 7, 3, 12, 19, 15, 11, 24],
+    &[4, 5, 6, 7, 16, 17, 18, 19, 25],
+];
+
+const PENTA_EDGES: [&[usize]; 9] = [
+    &[0, 1, 6],
+    &[1, 2, 7],
+    &[2, 0, 8],
+    &[0, 3, 9],
+    &[1, 4, 10],
+    &[2, 5, 11],
+    &[3, 4, 12],
+    &[4, 5, 13],
+    &[5, 3, 14],
+];
+// triangular faces first
+const PENTA_FACES: [&[usize]; 5] = [
+    &[0, 2, 1, 8, 7, 6],
+    &[3, 4, 5, 12, 13, 14],
+    &[0, 1, 4, 3, 6, 10, 12, 9, 15],
+    &[1, 2, 5, 4, 7, 11, 13, 10, 16],
+    &[2, 0, 3, 5, 8, 9, 14, 11, 17],
+];
+
+const TETRA_EDGES: [&[usize]; 6] = [
+    &[0, 1, 4],
+    &[1, 2, 5],
+    &[2, 0, 6],
+    &[0, 3, 7],
+    &[1, 3, 8],
+    &[2, 3, 9],
+];
+const TETRA_FACES: [&[usize]; 4] = [
+    &[0, 2, 1, 6, 5, 4],
+    &[0, 1, 3, 4, 8, 7],
+    &[1, 2, 3, 5, 9, 8],
+    &[0, 3, 2, 7, 9, 6],
+];
+
+const PYRAM_EDGES: [&[usize]; 8] = [
+    &[0, 1, 5],
+    &[1, 2, 6],
+    &[2, 3, 7],
+    &[3, 0, 8],
+    &[0, 4, 9],
+    &[1, 4, 10],
+    &[2, 4, 11],
+    &[3, 4, 12],
+];
+// quadrangular base first
+const PYRAM_FACES: [&[usize]; 5] = [
+    &[0, 3, 2, 1, 8, 7, 6, 5],
+    &[0, 1, 4, 5, 10, 9],
+    &[1, 2, 4, 6, 11, 10],
+    &[2, 3, 4, 7, 12, 11],
+    &[3, 0, 4, 8, 9, 12],
+];
+
+// entities keeping the first `ty.get_nb_of_connectivities()` indices of each list
+fn local_entities(lists: &[&'static [usize]], types: &[CellType]) -> Vec<LocalEntity> {
+    lists
+        .iter()
+        .zip(types.iter())
+        .map(|(nodes, ty)| LocalEntity {
+            ty: ty.clone(),
+            nodes: &nodes[..ty.get_nb_of_connectivities()],
+        })
+        .collect()
+}
+
+impl CellType {
+    // 0 for points, 1 for segments, 2 for surface cells and 3 for volume cells
+    pub fn topological_dimension(&self) -> usize {
+        match self {
+            CellType::POI1 => 0,
+            CellType::SEG2 | CellType::SEG3 | CellType::SEG4 => 1,
+            CellType::TRIA3
+            | CellType::TRIA6
+            | CellType::TRIA7
+            | CellType::QUAD4
+            | CellType::QUAD8
+            | CellType::QUAD9 => 2,
+            _ => 3,
+        }
+    }
+
+    // type with the same shape and corner nodes only, e.g. TETRA10 -> TETRA4
+    pub fn linear_type(&self) -> CellType {
+        match self {
+            CellType::SEG3 | CellType::SEG4 => CellType::SEG2,
+            CellType::TRIA6 | CellType::TRIA7 => CellType::TRIA3,
+            CellType::QUAD8 | CellType::QUAD9 => CellType::QUAD4,
+            CellType::HEXA20 | CellType::HEXA27 => CellType::HEXA8,
+            CellType::PENTA15 | CellType::PENTA18 => CellType::PENTA6,
+            CellType::TETRA10 => CellType::TETRA4,
+            CellType::PYRAM13 => CellType::PYRAM5,
+            _ => self.clone(),
+        }
+    }
+
+    pub fn is_linear(&self) -> bool {
+        self.linear_type() == *self
+    }
+
+    pub fn get_nb_of_corner_nodes(&self) -> usize {
+        match self {
+            CellType::SEG3 | CellType::SEG4 => 2,
+            _ => self.linear_type().get_nb_of_connectivities(),
+        }
+    }
+
+    // local indices of the corner nodes
+    pub fn corner_node_indices(&self) -> std::ops::Range<usize> {
+        0..self.get_nb_of_corner_nodes()
+    }
+
+    // local indices of the other nodes: mid-edge nodes, face and volume centers
+    pub fn mid_node_indices(&self) -> std::ops::Range<usize> {
+        self.get_nb_of_corner_nodes()..self.get_nb_of_connectivities()
+    }
+
+    // type of the edges, e.g. SEG3 for a HEXA20
+    fn edge_type(&self) -> CellType {
+        match self {
+            CellType::SEG4 => CellType::SEG4,
+            _ if self.is_linear() => CellType::SEG2,
+            _ => CellType::SEG3,
+        }
+    }
+
+    // edges of the cell, a segment is its own edge
+    pub fn edges(&self) -> Vec<LocalEntity> {
+        let lists: &[&'static [usize]] = match self.linear_type() {
+            CellType::POI1 => &[],
+            CellType::SEG2 => &SEG_EDGES,
+            CellType::TRIA3 => &TRIA_EDGES,
+            CellType::QUAD4 => &QUAD_EDGES,
+            CellType::HEXA8 => &HEXA_EDGES,
+            CellType::PENTA6 => &PENTA_EDGES,
+            CellType::TETRA4 => &TETRA_EDGES,
+            _ => &PYRAM_EDGES,
+        };
+        local_entities(lists, &vec![self.edge_type(); lists.len()])
+    }
+
+    // faces of the cell, a surface cell is its own face
+    pub fn faces(&self) -> Vec<LocalEntity> {
+        use CellType::*;
+        match self {
+            TRIA3 | TRIA6 | TRIA7 => local_entities(&TRIA_FACES, std::slice::from_ref(self)),
+            QUAD4 | QUAD8 | QUAD9 => local_entities(&QUAD_FACES, std::slice::from_ref(self)),
+            HEXA8 => local_entities(&HEXA_FACES, &[QUAD4, QUAD4, QUAD4, QUAD4, QUAD4, QUAD4]),
+            HEXA20 => local_entities(&HEXA_FACES, &[QUAD8, QUAD8, QUAD8, QUAD8, QUAD8, QUAD8]),
+            HEXA27 => local_entities(&HEXA_FACES, &[QUAD9, QUAD9, QUAD9, QUAD9, QUAD9, QUAD9]),
+            PENTA6 => local_entities(&PENTA_FACES, &[TRIA3, TRIA3, QUAD4, QUAD4, QUAD4]),
+            PENTA15 => local_entities(&PENTA_FACES, &[TRIA6, TRIA6, QUAD8, QUAD8, QUAD8]),
+            PENTA18 => local_entities(&PENTA_FACES, &[TRIA6, TRIA6, QUAD9, QUAD9, QUAD9]),
+            TETRA4 => local_entities(&TETRA_FACES, &[TRIA3, TRIA3, TRIA3, TRIA3]),
+            TETRA10 => local_entities(&TETRA_FACES, &[TRIA6, TRIA6, TRIA6, TRIA6]),
+            PYRAM5 => local_entities(&PYRAM_FACES, &[QUAD4, TRIA3, TRIA3, TRIA3, TRIA3]),
+            PYRAM13 => local_entities(&PYRAM_FACES, &[QUAD8, TRIA6, TRIA6, TRIA6, TRIA6]),
+            POI1 | SEG2 | SEG3 | SEG4 => vec![],
+        }
+    }
+
+    // Node order of the mirrored cell, whose orientation is reversed: node i of the mirrored
+    // cell is node `order[i]` of the cell. Corners are permuted by a symmetry of the
+    // reference element, mid nodes and face centers follow their edge or face
+    pub fn reversed_node_order(&self) -> Vec<usize> {
+        let corners: &[usize] = match self.linear_type() {
+            CellType::POI1 => &[0],
+            CellType::SEG2 => &[1, 0],
+            CellType::TRIA3 => &[0, 2, 1],
+            CellType::QUAD4 => &[0, 3, 2, 1],
+            CellType::HEXA8 => &[0, 3, 2, 1, 4, 7, 6, 5],
+            CellType::PENTA6 => &[0, 2, 1, 3, 5, 4],
+            CellType::TETRA4 => &[0, 2, 1, 3],
+            _ => &[0, 3, 2, 1, 4],
+        };
+        let mut order: Vec<usize> = (0..self.get_nb_of_connectivities()).collect();
+        order[..corners.len()].copy_from_slice(corners);
+        let edges = self.edges();
+        for edge in edges.iter() {
+            let (a, b) = (corners[edge.nodes[0]], corners[edge.nodes[1]]);
+            let Some(image) = edges.iter().find(|other| {
+                other.nodes[..2] == [a, b] || other.nodes[..2] == [b, a]
+            }) else {
+                continue;
+            };
+            // mid nodes are listed from the first corner of their edge
+            let mut mids = image.nodes[2..].to_vec();
+            if image.nodes[0] != a {
+                mids.reverse();
+            }
+            for (mid, image_mid) in edge.nodes[2..].iter().zip(mids) {
+                order[*mid] = image_mid;
+            }
+        }
+        let faces = self.faces();
+        for face in faces.iter() {
+            let nb_corners = face.ty.get_nb_of_corner_nodes();
+            if face.nodes.len() != 2 * nb_corners + 1 {
+                continue;
+            }
+            let image_corners: Vec<usize> =
+                face.nodes[..nb_corners].iter().map(|i| corners[*i]).collect();
+            if let Some(image) = faces.iter().find(|other| {
+                other.nodes.len() == face.nodes.len()
+                    && image_corners.iter().all(|i| other.nodes[..nb_corners].contains(i))
+            }) {
+                order[face.nodes[2 * nb_corners]] = image.nodes[2 * nb_corners];
+            }
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::mesh_enums::CellType;
+
+    const ALL_TYPES: [CellType; 20] = [
+        CellType::POI1,
+        CellType::SEG2,
+        CellType::SEG3,
+        CellType::SEG4,
+        CellType::TRIA3,
+        CellType::TRIA6,
+        CellType::TRIA7,
+        CellType::QUAD4,
+        CellType::QUAD8,
+        CellType::QUAD9,
+        CellType::HEXA8,
+        CellType::HEXA20,
+        CellType::HEXA27,
+        CellType::PENTA6,
+        CellType::PENTA15,
+        CellType::PENTA18,
+        CellType::TETRA4,
+        CellType::TETRA10,
+        CellType::PYRAM5,
+        CellType::PYRAM13,
+    ];
+
+    // corner coordinates of the Code_aster reference elements
+    fn reference_corners(cell_type: &CellType) -> Vec<[f64; 3]> {
+        match cell_type.linear_type() {
+            CellType::HEXA8 => vec![
+                [-1., -1., -1.],
+                [1., -1., -1.],
+                [1., 1., -1.],
+                [-1., 1., -1.],
+                [-1., -1., 1.],
+                [1., -1., 1.],
+                [1., 1., 1.],
+                [-1., 1., 1.],
+            ],
+            CellType::PENTA6 => vec![
+                [-1., 1., 0.],
+                [-1., 0., 1.],
+                [-1., 0., 0.],
+                [1., 1., 0.],
+                [1., 0., 1.],
+                [1., 0., 0.],
+            ],
+            CellType::TETRA4 => vec![[0., 1., 0.], [0., 0., 1.], [0., 0., 0.], [1., 0., 0.]],
+            CellType::PYRAM5 => vec![
+                [1., 0., 0.],
+                [0., 1., 0.],
+                [-1., 0., 0.],
+                [0., -1., 0.],
+                [0., 0., 1.],
+            ],
+            _ => vec![],
+        }
+    }
+
+    fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+        [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+    }
+
+    fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    }
+
+    fn centroid(points: &[[f64; 3]]) -> [f64; 3] {
+        let n = points.len() as f64;
+        let mut c = [0.; 3];
+        for point in points {
+            for k in 0..3 {
+                c[k] += point[k] / n;
+            }
+        }
+        c
+    }
+
+    #[test]
+    fn topology_counts_should_work() {
+        assert_eq!(CellType::HEXA20.topological_dimension(), 3);
+        assert_eq!(CellType::QUAD9.topological_dimension(), 2);
+        assert_eq!(CellType::POI1.topological_dimension(), 0);
+        assert_eq!(CellType::TETRA10.linear_type(), CellType::TETRA4);
+        assert_eq!(CellType::PYRAM13.corner_node_indices(), 0..5);
+        assert_eq!(CellType::PYRAM13.mid_node_indices(), 5..13);
+        assert_eq!(CellType::SEG4.corner_node_indices(), 0..2);
+        assert_eq!(CellType::HEXA27.edges().len(), 12);
+        assert_eq!(CellType::PENTA6.faces().len(), 5);
+        assert_eq!(CellType::SEG3.edges()[0].nodes, &[0, 1, 2]);
+        assert!(CellType::SEG2.faces().is_empty());
+    }
+
+    #[test]
+    fn sub_entities_should_have_the_size_of_their_type() {
+        for cell_type in ALL_TYPES.iter() {
+            for entity in cell_type.edges().iter().chain(cell_type.faces().iter()) {
+                assert_eq!(entity.nodes.len(), entity.ty.get_nb_of_connectivities());
+                assert!(entity.nodes.iter().all(|i| *i < cell_type.get_nb_of_connectivities()));
+            }
+        }
+        let faces = CellType::HEXA20.faces();
+        assert!(faces.iter().all(|face| face.ty == CellType::QUAD8));
+        let faces = CellType::PENTA18.faces();
+        assert_eq!(faces[0].ty, CellType::TRIA6);
+        assert_eq!(faces[4].ty, CellType::QUAD9);
+    }
+
+    #[test]
+    fn face_mid_nodes_should_match_edges() {
+        for cell_type in ALL_TYPES.iter().filter(|ty| ty.topological_dimension() >= 2) {
+            let edges = cell_type.edges();
+            for face in cell_type.faces() {
+                let corners = face.ty.get_nb_of_corner_nodes();
+                for (i, mid) in face.nodes[corners..].iter().take(corners).enumerate() {
+                    let (a, b) = (face.nodes[i], face.nodes[(i + 1) % corners]);
+                    assert!(
+                        edges.iter().any(|edge| edge.nodes[2] == *mid
+                            && (edge.nodes[..2] == [a, b] || edge.nodes[..2] == [b, a])),
+                        "{} face {:?}",
+                        cell_type,
+                        face.nodes
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reversed_node_order_should_be_an_involution() {
+        for cell_type in ALL_TYPES.iter() {
+            let order = cell_type.reversed_node_order();
+            let mut sorted = order.clone();
+            sorted.sort();
+            assert_eq!(sorted, (0..cell_type.get_nb_of_connectivities()).collect::<Vec<_>>());
+            for (i, j) in order.iter().enumerate() {
+                assert_eq!(order[*j], i, "{}", cell_type);
+            }
+        }
+        assert_eq!(CellType::SEG4.reversed_node_order(), vec![1, 0, 3, 2]);
+        assert_eq!(CellType::QUAD9.reversed_node_order(), vec![0, 3, 2, 1, 7, 6, 5, 4, 8]);
+    }
+
+    #[test]
+    fn volume_faces_should_point_outwards() {
+        for cell_type in ALL_TYPES.iter().filter(|ty| ty.topological_dimension() == 3) {
+            let corners = reference_corners(cell_type);
+            let center = centroid(&corners);
+            for face in cell_type.faces() {
+                let points: Vec<[f64; 3]> = face.nodes[..face.ty.get_nb_of_corner_nodes()]
+                    .iter()
+                    .map(|i| corners[*i])
+                    .collect();
+                let normal = cross(sub(points[1], points[0]), sub(points[2], points[0]));
+                let outwards = sub(centroid(&points), center);
+                let dot: f64 = (0..3).map(|k| normal[k] * outwards[k]).sum();
+                assert!(dot > 0., "{} face {:?}", cell_type, face.nodes);
+            }
+        }
+    }
+}
@@ -2,15 +2,29 @@ extern crate core;
 
 mod mesh;
 mod mesh_enums;
+mod mesh_errors;
 mod node;
 mod cell;
+mod cell_topology;
+mod shape_functions;
 mod parsers;
+mod writers;
 
 pub mod lib{
-    pub use crate::mesh::Mesh;
-    pub use crate::mesh_enums::{CellType, MeshFormat};
+    pub use crate::mesh::{
+        AffineTransform, CellDefect, CellQuality, GroupMeasures, IdMapping, MergeOptions, Mesh,
+        MetricStatistics, NamingScheme, NodeCells, QualityReport, RenumberingReport,
+        ValidationReport,
+    };
+    pub use crate::mesh_enums::{
+        CellType, DefectKind, GroupMergePolicy, MeshFormat, NameClashPolicy, NodeRemovalPolicy,
+        OrientationReference, QualityMetric, RenumberingAlgorithm, ValidationIssue,
+    };
+    pub use crate::mesh_errors::{MeshError, ReferencedBy};
     pub use crate::node::Node;
     pub use crate::cell::MeshCell;
+    pub use crate::cell_topology::LocalEntity;
     pub use crate::parsers::mail_parser;
+    pub use crate::writers::mail_writer;
 }
 
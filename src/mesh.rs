@@ -1,14 +1,44 @@
-use core::fmt;
 use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::PathBuf;
 
 use crate::cell::MeshCell;
-use crate::lib::mail_parser::mail_parser;
+use crate::lib::mail_parser::{mail_parser, mail_syntax_error};
+use crate::lib::mail_writer::mail_writer;
 use crate::mesh_enums::{CellType, MeshFormat};
+use crate::mesh_errors::{MeshError, ReferencedBy};
 use crate::node::Node;
 use crate::parsers::tokens::{CellProp, MailParseOutput, NodeProp};
 
+mod assembly;
+mod boundary;
+mod cell_graph;
+mod coincident_nodes;
+mod defects;
+mod extraction;
+mod geometry;
+mod groups;
+mod names;
+mod node_cells;
+mod node_ordering;
+mod orientation;
+mod quality;
+mod removal;
+mod renumbering;
+mod transform;
+mod validation;
+
+pub use assembly::MergeOptions;
+pub use defects::CellDefect;
+pub use geometry::GroupMeasures;
+pub use names::NamingScheme;
+pub use node_cells::NodeCells;
+pub use node_ordering::RenumberingReport;
+pub use quality::{CellQuality, MetricStatistics, QualityReport};
+pub use renumbering::IdMapping;
+pub use transform::AffineTransform;
+pub use validation::ValidationReport;
+
 // cn [ndarray]: coordonnées des noeuds    (nb_nodes x dim)
 // co [dict]: connectivités des mailles
 // ty [ndarray]: numéro de type de maille
@@ -24,23 +54,10 @@ use crate::parsers::tokens::{CellProp, MailParseOutput, NodeProp};
 // self.ty = ty if ty is not None else []      # list of cells' type
 // self.ty = np.array(self.ty, dtype=np.uint8)
 
-pub struct MeshError {
-    message: String,
-}
-
-impl fmt::Display for MeshError {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "MeshError {}", self.message.clone())
-    }
-}
-impl fmt::Debug for MeshError {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "MeshError {}", self.message.clone())
-    }
-}
-
 #[derive(Debug)]
 pub struct Mesh {
+    // dimension de l'espace (1, 2 ou 3), i.e. COOR_1D, COOR_2D or COOR_3D in .mail files
+    pub dim: usize,
     pub next_node_id: usize,
     pub next_cell_id: usize,
     // noeuds
@@ -51,41 +68,67 @@ pub struct Mesh {
     pub gno: HashMap<Box<str>, Vec<usize>>,
     // gma [Group]: groupes de mailles (dictionnaire de arrays de numéros de mailles)
     pub gma: HashMap<Box<str>, Vec<usize>>,
+    // automatic naming of nodes and cells created without a name
+    pub naming: NamingScheme,
     // mapping from node names to node ids
     nodes_name_to_id: HashMap<Box<str>, usize>,
+    // mapping from node ids to node names
+    nodes_id_to_name: HashMap<usize, Box<str>>,
     // mapping from cell names to node ids
     cells_name_to_id: HashMap<Box<str>, usize>,
+    // mapping from cell ids to cell names
+    cells_id_to_name: HashMap<usize, Box<str>>,
 }
 
 impl<'a> Mesh {
     pub fn new() -> Self {
         Mesh {
+            dim: 3,
             next_node_id: 0,
             next_cell_id: 0,
             nodes: HashMap::new(),
             cells: HashMap::new(),
             gno: HashMap::new(),
             gma: HashMap::new(),
+            naming: NamingScheme::default(),
             nodes_name_to_id: HashMap::new(),
+            nodes_id_to_name: HashMap::new(),
             cells_name_to_id: HashMap::new(),
+            cells_id_to_name: HashMap::new(),
         }
     }
 
-    pub fn add_nodes(&mut self, nodes: Vec<NodeProp<'a>>) {
-        for inode in 0..nodes.len() {
-            let node_prop = &nodes[inode];
+    // nodes without name are named with the naming scheme
+    pub fn add_nodes(&mut self, nodes: Vec<NodeProp<'a>>) -> Result<(), MeshError> {
+        for node_prop in nodes.iter() {
             let node_tmp = Node {
-                x: node_prop.x.into(),
-                y: node_prop.y.into(),
-                z: node_prop.z.into(),
+                x: node_prop.x,
+                y: node_prop.y,
+                z: node_prop.z,
             };
-            self.nodes.insert(self.next_node_id, node_tmp);
-            if let Some(node_name) = node_prop.name {
-                self.nodes_name_to_id
-                    .insert(node_name.into(), self.next_node_id);
-            } // incrément du prochain node_id
-            self.next_node_id += 1;
+            self.add_a_node(node_tmp, node_prop.name)?;
+        }
+        Ok(())
+    }
+
+    pub fn add_a_node(&mut self, node: Node, name: Option<&str>) -> Result<usize, MeshError> {
+        if let Some(node_name) = name {
+            names::check_node_name(node_name)?;
+            if self.nodes_name_to_id.contains_key(node_name) {
+                return Err(MeshError::DuplicateNodeName(node_name.to_string()));
+            }
         }
+        let node_id = self.next_node_id;
+        self.nodes.insert(node_id, node);
+        match name {
+            Some(node_name) => {
+                self.nodes_name_to_id.insert(node_name.into(), node_id);
+                self.nodes_id_to_name.insert(node_id, node_name.into());
+            }
+            None => self.name_node_automatically(node_id),
+        } // incrément du prochain node_id
+        self.next_node_id += 1;
+        Ok(node_id)
     }
 
     pub fn edit_node(
@@ -94,58 +137,92 @@ impl<'a> Mesh {
         x: Option<f64>,
         y: Option<f64>,
         z: Option<f64>,
-    ) -> bool {
-        if let Some(node) = self.nodes.get_mut(index) {
-            // let &mut node_tmp = node;
+    ) -> Result<(), MeshError> {
+        let node = self
+            .nodes
+            .get_mut(index)
+            .ok_or(MeshError::UnknownNodeId(*index))?;
+        if let Some(val) = x {
+            node.x = val;
+        }
+        if let Some(val) = y {
+            node.y = val;
+        }
+        if let Some(val) = z {
+            node.z = val;
+        }
+        Ok(())
+    }
 
-            if let Some(val) = x {
-                node.x = val;
-            }
-            if let Some(val) = y {
-                node.y = val;
-            }
-            if let Some(val) = z {
-                node.z = val;
-            }
-            true
-        } else {
-            false
+    // checks that every node id is contained in mesh
+    fn check_node_ids(&self, node_ids: &[usize]) -> Result<(), MeshError> {
+        match node_ids.iter().find(|node_id| !self.nodes.contains_key(node_id)) {
+            Some(node_id) => Err(MeshError::UnknownNodeId(*node_id)),
+            None => Ok(()),
         }
     }
 
-    pub fn add_cells_from_properties(&mut self, cells: Vec<CellProp<'a>>) {
-        for icell in 0..cells.len() {
-            let cell_prop = &cells[icell];
-            let connectivities: Vec<usize> = cell_prop
+    // checks that every cell id is contained in mesh
+    fn check_cell_ids(&self, cell_ids: &[usize]) -> Result<(), MeshError> {
+        match cell_ids.iter().find(|cell_id| !self.cells.contains_key(cell_id)) {
+            Some(cell_id) => Err(MeshError::UnknownCellId(*cell_id)),
+            None => Ok(()),
+        }
+    }
+
+    pub fn add_cells_from_properties(&mut self, cells: Vec<CellProp<'a>>) -> Result<(), MeshError> {
+        for cell_prop in cells.iter() {
+            if let Some(name) = cell_prop.name {
+                names::check_cell_name(name)?;
+                if self.cells_name_to_id.contains_key(name) {
+                    return Err(MeshError::DuplicateCellName(name.to_string()));
+                }
+            }
+            let connectivities = cell_prop
                 .nodes
                 .iter()
-                .map(|x| {
-                    if let Some(node_id) = self.nodes_name_to_id.get(*x) {
-                        return *node_id;
-                    }
-                    panic!("Node {} not found in Node name to id mapping : {:#?}", x, self.nodes_name_to_id);
+                .map(|x| match self.nodes_name_to_id.get(*x) {
+                    Some(node_id) => Ok(*node_id),
+                    None => Err(MeshError::UnknownNodeName {
+                        name: x.to_string(),
+                        referenced_by: cell_prop
+                            .name
+                            .map(|name| ReferencedBy::Cell(name.to_string())),
+                    }),
                 })
-                .collect();
-            let cell_tmp = MeshCell {
-                ty: cell_prop.cell_type.clone(),
-                co: connectivities,
-            };
-            self.cells.insert(self.next_cell_id, cell_tmp);
-            if let Some(name) = cell_prop.name {
-                self.cells_name_to_id.insert(name.into(), self.next_cell_id);
-            } // incrément du prochain node_id
-            self.next_cell_id += 1;
+                .collect::<Result<Vec<usize>, MeshError>>()?;
+            let cell_tmp = MeshCell::new(cell_prop.cell_type.clone(), &connectivities)
+                .map_err(|error| match error {
+                    MeshError::ConnectivitySize {
+                        cell_type,
+                        expected,
+                        actual,
+                        ..
+                    } => MeshError::ConnectivitySize {
+                        cell_type,
+                        expected,
+                        actual,
+                        cell_name: cell_prop.name.map(|name| name.to_string()),
+                    },
+                    error => error,
+                })?;
+            self.insert_new_cell(cell_tmp, cell_prop.name);
         }
+        Ok(())
     }
 
     pub fn add_cells(
         &mut self,
         connectivities: &[Vec<usize>],
         ty: CellType,
-    ) -> Result<Vec<usize>, &str> {
+    ) -> Result<Vec<usize>, MeshError> {
         self.add_cells_of_type(ty, connectivities)
     }
 
+    pub fn get_node_name(node_id: usize) -> String {
+        format!("N{}", &(node_id + 1))
+    }
+
     pub fn get_cell_name(cell_id: usize) -> String {
         format!("M{}", &(cell_id + 1))
     }
@@ -153,10 +230,7 @@ impl<'a> Mesh {
     pub fn get_cell_co(&self, cell_id: usize) -> Result<Vec<usize>, MeshError> {
         let node_ids = match self.cells.get(&cell_id) {
             Some(val) => val.get_co(),
-            None => {
-                let mess = format!("cell_id {} not found in cells", cell_id);
-                return Err(MeshError { message: mess });
-            }
+            None => return Err(MeshError::UnknownCellId(cell_id)),
         };
 
         Ok(node_ids)
@@ -164,31 +238,56 @@ impl<'a> Mesh {
 
     pub fn create_one_cell(
         cell_type: CellType,
-        connectivity: &Vec<usize>,
-    ) -> Result<MeshCell, &'static str> {
+        connectivity: &[usize],
+    ) -> Result<MeshCell, MeshError> {
         MeshCell::new(cell_type, connectivity)
     }
 
     pub fn add_a_cell(
         &mut self,
         cell_type: CellType,
-        connectivity: &Vec<usize>,
-    ) -> Result<usize, &'static str> {
-        let cell = match Self::create_one_cell(cell_type.clone(), connectivity) {
-            Ok(p) => p,
-            Err(e) => return Err(e),
-        };
-        self.cells.insert(self.next_cell_id, cell);
+        connectivity: &[usize],
+    ) -> Result<usize, MeshError> {
+        let cell = Self::create_one_cell(cell_type.clone(), connectivity)?;
+        self.check_node_ids(connectivity)?;
+        Ok(self.insert_new_cell(cell, None))
+    }
+
+    pub fn add_a_named_cell(
+        &mut self,
+        cell_type: CellType,
+        connectivity: &[usize],
+        name: &str,
+    ) -> Result<usize, MeshError> {
+        let cell = Self::create_one_cell(cell_type.clone(), connectivity)?;
+        self.check_node_ids(connectivity)?;
+        names::check_cell_name(name)?;
+        if self.cells_name_to_id.contains_key(name) {
+            return Err(MeshError::DuplicateCellName(name.to_string()));
+        }
+        Ok(self.insert_new_cell(cell, Some(name)))
+    }
+
+    // cells without name are named with the naming scheme
+    fn insert_new_cell(&mut self, cell: MeshCell, name: Option<&str>) -> usize {
         let cell_id = self.next_cell_id;
+        self.cells.insert(cell_id, cell);
+        match name {
+            Some(cell_name) => {
+                self.cells_name_to_id.insert(cell_name.into(), cell_id);
+                self.cells_id_to_name.insert(cell_id, cell_name.into());
+            }
+            None => self.name_cell_automatically(cell_id),
+        } // incrément du prochain cell_id
         self.next_cell_id += 1;
-        Ok(cell_id)
+        cell_id
     }
 
     pub fn add_cells_of_type(
         &mut self,
         cell_type: CellType,
         connectivities: &[Vec<usize>],
-    ) -> Result<Vec<usize>, &'static str> {
+    ) -> Result<Vec<usize>, MeshError> {
         let mut cells = vec![];
         for nodes in connectivities.iter() {
             let cell_id = self.add_a_cell(cell_type.clone(), nodes)?;
@@ -197,84 +296,78 @@ impl<'a> Mesh {
         Ok(cells)
     }
 
-    fn extract_cell_result(cell: Result<MeshCell, &'static str>) -> Result<MeshCell, &'static str> {
-        match cell {
-            Ok(val) => Ok(val),
-            Err(e) => Err(e),
-        }
-    }
-
-    pub fn edit_cell(&mut self, index: usize, connectivity: &Vec<usize>, ty: CellType) -> bool {
-        let val = Self::extract_cell_result(MeshCell::new(ty, connectivity));
-        match val {
-            Ok(ok_val) => {
-                if self.cells.insert(index, ok_val).is_none() {
-                    return false;
-                };
-                true
-            }
-            Err(_e) => false,
-        }
+    pub fn edit_cell(
+        &mut self,
+        index: usize,
+        connectivity: &[usize],
+        ty: CellType,
+    ) -> Result<(), MeshError> {
+        self.check_cell_ids(&[index])?;
+        let cell = MeshCell::new(ty, connectivity)?;
+        self.check_node_ids(connectivity)?;
+        self.cells.insert(index, cell);
+        Ok(())
     }
     pub fn create_node_group(
         &mut self,
         name: &str,
-        node_ids: &Vec<usize>,
-    ) -> Result<(), &'static str> {
-        let unique_node_ids: HashSet<usize> = node_ids.clone().into_iter().collect();
-        let existing_node_ids: HashSet<usize> = self.nodes.keys().cloned().collect();
-        let intersection = existing_node_ids
-            .intersection(&unique_node_ids)
-            .collect::<Vec<&usize>>();
-
-        if intersection.len() != unique_node_ids.len() {
-            return Err("At least one node_id is not contained in mesh");
-        }
+        node_ids: &[usize],
+    ) -> Result<(), MeshError> {
+        self.check_node_ids(node_ids)?;
+        let unique_node_ids: HashSet<usize> = node_ids.iter().copied().collect();
         let mut target_node_ids = unique_node_ids.into_iter().collect::<Vec<usize>>();
         target_node_ids.sort();
-        self.gma.insert(String::from(name).into_boxed_str(), target_node_ids);
+        self.gno.insert(String::from(name).into_boxed_str(), target_node_ids);
         Ok(())
     }
     pub fn create_cell_group(
         &mut self,
         name: &str,
-        cell_ids: &Vec<usize>,
-    ) -> Result<(), &'static str> {
+        cell_ids: &[usize],
+    ) -> Result<(), MeshError> {
+        self.check_cell_ids(cell_ids)?;
         let unique_cell_ids: HashSet<usize> = cell_ids.iter().copied().collect();
-        let existing_cell_ids: HashSet<usize> = self.cells.keys().cloned().collect();
-        let intersection = existing_cell_ids
-            .intersection(&unique_cell_ids)
-            .collect::<Vec<&usize>>();
-
-        if intersection.len() != unique_cell_ids.len() {
-            return Err("At least one cell_id is not contained in mesh");
-        }
         let mut target_cell_ids = unique_cell_ids.into_iter().collect::<Vec<usize>>();
         target_cell_ids.sort();
         self.gma.insert(String::from(name).into_boxed_str(), target_cell_ids);
         Ok(())
     }
 
-    pub fn create_from_parser_output(parser_output: MailParseOutput) -> Result<Self, &'static str> {
+    pub fn create_from_parser_output(parser_output: MailParseOutput) -> Result<Self, MeshError> {
         let mut mesh = Mesh::new();
 
-        mesh.add_nodes(parser_output.nodes.to_owned());
-        mesh.add_cells_from_properties(parser_output.cells.to_owned());
+        if let Some(dim) = parser_output.dim {
+            mesh.dim = dim;
+        }
+        mesh.add_nodes(parser_output.nodes.to_owned())?;
+        mesh.add_cells_from_properties(parser_output.cells.to_owned())?;
 
         for group in parser_output.groups {
             match group.group_type {
                 crate::parsers::tokens::GroupType::Node => {
-                    let node_ids: Vec<usize> = group.elems
+                    let node_ids = group.elems
                         .iter()
-                        .map(|x| *mesh.nodes_name_to_id.get(*x).unwrap())
-                        .collect();
+                        .map(|x| match mesh.nodes_name_to_id.get(*x) {
+                            Some(node_id) => Ok(*node_id),
+                            None => Err(MeshError::UnknownNodeName {
+                                name: x.to_string(),
+                                referenced_by: Some(ReferencedBy::Group(group.name.to_string())),
+                            }),
+                        })
+                        .collect::<Result<Vec<usize>, MeshError>>()?;
                     mesh.create_node_group(group.name, &node_ids)?;
                 }
                 crate::parsers::tokens::GroupType::Cell => {
                     let cell_ids = group.elems
                         .iter()
-                        .map(|x| *mesh.cells_name_to_id.get(*x).unwrap())
-                        .collect();
+                        .map(|x| match mesh.cells_name_to_id.get(*x) {
+                            Some(cell_id) => Ok(*cell_id),
+                            None => Err(MeshError::UnknownCellName {
+                                name: x.to_string(),
+                                referenced_by: Some(ReferencedBy::Group(group.name.to_string())),
+                            }),
+                        })
+                        .collect::<Result<Vec<usize>, MeshError>>()?;
                     mesh.create_cell_group(group.name, &cell_ids)?;
                 }
             };
@@ -283,10 +376,11 @@ impl<'a> Mesh {
         Ok(mesh)
     }
 
-    pub fn read_mesh(filename: PathBuf, format: MeshFormat) -> Result<Self, String> {
-        println!("Reading file {}", filename.display());
-
-        let content = fs::read_to_string(filename).expect("Something went wrong reading the file");
+    pub fn read_mesh(filename: PathBuf, format: MeshFormat) -> Result<Self, MeshError> {
+        let content = fs::read_to_string(&filename).map_err(|source| MeshError::Io {
+            path: filename.clone(),
+            source,
+        })?;
 
         let output = match format {
             MeshFormat::Mail => Mesh::read_mail_format(&content),
@@ -296,16 +390,31 @@ impl<'a> Mesh {
         Ok(mesh)
     }
 
-    pub fn read_mail_format(content: &'a str) -> Result<MailParseOutput<'a>, String> {
+    pub fn read_mail_format(content: &'a str) -> Result<MailParseOutput<'a>, MeshError> {
         let output = mail_parser(content);
         match output {
             Ok(val) => Ok(val),
-            Err(err) => Err(err.to_string()),
+            Err(err) => Err(mail_syntax_error(content, &err)),
         }
     }
+
+    pub fn write_mesh(&self, filename: PathBuf, format: MeshFormat) -> Result<(), MeshError> {
+        let content = match format {
+            MeshFormat::Mail => self.write_mail_format(),
+        };
+
+        fs::write(&filename, content).map_err(|source| MeshError::Io {
+            path: filename.clone(),
+            source,
+        })
+    }
+
+    pub fn write_mail_format(&self) -> String {
+        mail_writer(self)
+    }
 }
 
-impl<'a> Default for Mesh {
+impl Default for Mesh {
     fn default() -> Self {
         Self::new()
     }
@@ -314,10 +423,12 @@ impl<'a> Default for Mesh {
 #[cfg(test)]
 mod tests {
 
+    use crate::lib::mail_parser::mail_parser;
     use crate::mesh::Mesh;
     use crate::mesh_enums::CellType;
+    use crate::mesh_errors::{MeshError, ReferencedBy};
     use crate::node::Node;
-    use crate::parsers::tokens::NodeProp;
+    use crate::parsers::tokens::{CellProp, NodeProp};
 
     #[test]
     fn mesh_init_empty_should_work() {
@@ -326,6 +437,64 @@ mod tests {
         assert_eq!(mesh.nodes.len(), 0);
         assert_eq!(mesh.gno.len(), 0);
         assert_eq!(mesh.gma.len(), 0);
+        assert_eq!(mesh.dim, 3);
+    }
+
+    #[test]
+    fn mesh_from_parser_output_should_keep_dimension() {
+        let output = mail_parser("COOR_2D\nN1 2. 3.\nN2 4. 5.\nFINSF\nFIN").unwrap();
+        let mesh = Mesh::create_from_parser_output(output).unwrap();
+        assert_eq!(mesh.dim, 2);
+        assert_eq!(mesh.nodes[&1], Node { x: 4., y: 5., z: 0. });
+    }
+
+    #[test]
+    fn mesh_from_parser_output_should_report_bad_references() {
+        let output = mail_parser("COOR_3D\nN1 2. 3. 0.\nFINSF\nSEG2\nM1 N1 N2\nFINSF\nFIN").unwrap();
+        assert!(matches!(
+            Mesh::create_from_parser_output(output),
+            Err(MeshError::UnknownNodeName { name, referenced_by: Some(ReferencedBy::Cell(cell)) })
+                if name == "N2" && cell == "M1"
+        ));
+        let output = mail_parser("COOR_3D\nN1 2. 3. 0.\nFINSF\nGROUP_NO\nBORD\nN1 N3\nFINSF\nFIN").unwrap();
+        assert!(matches!(
+            Mesh::create_from_parser_output(output),
+            Err(MeshError::UnknownNodeName { name, referenced_by: Some(ReferencedBy::Group(group)) })
+                if name == "N3" && group == "BORD"
+        ));
+        let output = mail_parser("COOR_3D\nN1 2. 3. 0.\nFINSF\nPOI1\nM1 N1\nFINSF\nGROUP_MA\nAPPUI\nM1 M2\nFINSF\nFIN").unwrap();
+        assert!(matches!(
+            Mesh::create_from_parser_output(output),
+            Err(MeshError::UnknownCellName { name, referenced_by: Some(ReferencedBy::Group(group)) })
+                if name == "M2" && group == "APPUI"
+        ));
+        let output = mail_parser("COOR_3D\nN1 2. 3. 0.\nN1 4. 3. 0.\nFINSF\nFIN").unwrap();
+        assert!(matches!(
+            Mesh::create_from_parser_output(output),
+            Err(MeshError::DuplicateNodeName(name)) if name == "N1"
+        ));
+        let output = mail_parser("COOR_3D\nN1 2. 3. 0.\nFINSF\nPOI1\nM1 N1\nM1 N1\nFINSF\nFIN").unwrap();
+        assert!(matches!(
+            Mesh::create_from_parser_output(output),
+            Err(MeshError::DuplicateCellName(name)) if name == "M1"
+        ));
+    }
+
+    #[test]
+    fn mesh_add_cells_from_properties_should_check_connectivity_size() {
+        let mut mesh = get_mesh_with_six_nodes();
+        let cells = vec![CellProp {
+            cell_type: CellType::SEG2,
+            name: Some("M1"),
+            nodes: vec!["N1"],
+        }];
+        let error = mesh.add_cells_from_properties(cells).unwrap_err();
+        assert!(matches!(
+            &error,
+            MeshError::ConnectivitySize { cell_name: Some(name), .. } if name == "M1"
+        ));
+        assert!(error.to_string().ends_with("(cell M1)"));
+        assert_eq!(mesh.cells.len(), 0);
     }
 
     #[test]
@@ -345,7 +514,7 @@ mod tests {
                 name: Some("N2"),
             },
         ];
-        mesh.add_nodes(nodes);
+        mesh.add_nodes(nodes).unwrap();
         assert_eq!(mesh.nodes.len(), 2);
         let _node = mesh.nodes.get(&0);
         assert_eq!(
@@ -380,7 +549,7 @@ mod tests {
                 name: Some("N222"),
             },
         ];
-        mesh.add_nodes(nodes);
+        mesh.add_nodes(nodes).unwrap();
         assert_eq!(mesh.nodes.len(), 4);
         assert_eq!(
             (*mesh.nodes.get(&2).unwrap()),
@@ -440,7 +609,7 @@ mod tests {
                 name: Some("N6"),
             },
         ];
-        mesh.add_nodes(nodes);
+        mesh.add_nodes(nodes).unwrap();
         mesh
     }
 
@@ -461,7 +630,7 @@ mod tests {
         assert_eq!(mesh.cells.len(), 2);
 
         let cell_co_not_found = mesh.get_cell_co(1112);
-        assert!(matches!(cell_co_not_found, Err(_mesh_error)));
+        assert!(matches!(cell_co_not_found, Err(MeshError::UnknownCellId(1112))));
 
         let cell_co_1 = mesh.get_cell_co(0).unwrap();
         assert_eq!(cell_co_1.len(), 1);
@@ -487,9 +656,8 @@ mod tests {
         assert_eq!(cell_co_2[1], 3);
     }
 
-    fn add_two_seg2_cells(mesh: &mut Mesh) -> Result<Vec<usize>, &str> {
-        let new_cells = mesh.add_cells(&[vec![0, 1], vec![2, 3]], CellType::SEG2);
-        new_cells
+    fn add_two_seg2_cells(mesh: &mut Mesh) -> Result<Vec<usize>, MeshError> {
+        mesh.add_cells(&[vec![0, 1], vec![2, 3]], CellType::SEG2)
     }
 
     #[test]
@@ -498,7 +666,7 @@ mod tests {
         {
             let _new_cells = add_two_seg2_cells(&mut mesh);
             let result = mesh.edit_node(&0, Some(10.2_f64), Some(0.2_f64), None);
-            assert!(result);
+            assert!(result.is_ok());
             let first_node = &mesh.nodes[&0];
 
             assert_eq!(first_node.x, 10.2_f64);
@@ -507,6 +675,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn editing_unknown_entities_should_fail() {
+        let mut mesh = get_mesh_with_six_nodes();
+        let _new_cells = add_two_seg2_cells(&mut mesh);
+        assert!(matches!(
+            mesh.edit_node(&10, Some(1.), None, None),
+            Err(MeshError::UnknownNodeId(10))
+        ));
+        assert!(matches!(
+            mesh.edit_cell(5, &[0, 1], CellType::SEG2),
+            Err(MeshError::UnknownCellId(5))
+        ));
+        assert!(!mesh.cells.contains_key(&5));
+        assert!(matches!(
+            mesh.edit_cell(0, &[0, 10], CellType::SEG2),
+            Err(MeshError::UnknownNodeId(10))
+        ));
+        assert!(matches!(
+            mesh.edit_cell(0, &[0], CellType::SEG2),
+            Err(MeshError::ConnectivitySize { cell_type: CellType::SEG2, expected: 2, actual: 1, cell_name: None })
+        ));
+        assert_eq!(mesh.cells[&0].co, vec![0, 1]);
+    }
+
+    #[test]
+    fn mesh_add_cells_should_return_err_when_node_is_unknown() {
+        let mut mesh = get_mesh_with_six_nodes();
+        let new_cells = mesh.add_cells(&[vec![0, 6]], CellType::SEG2);
+        assert!(matches!(new_cells, Err(MeshError::UnknownNodeId(6))));
+        assert_eq!(mesh.cells.len(), 0);
+    }
+
     #[test]
     fn should_be_able_to_edit_a_cell() {
         let mut mesh = get_mesh_with_six_nodes();
@@ -514,7 +714,7 @@ mod tests {
             let _new_cells = add_two_seg2_cells(&mut mesh);
             let first_cell = &mesh.cells[&0];
             assert_eq!(first_cell.get_co().len(), 2);
-            mesh.edit_cell(0, &vec![0], CellType::POI1);
+            mesh.edit_cell(0, &[0], CellType::POI1).unwrap();
             let first_cell = &mesh.cells[&0];
             assert_eq!(first_cell.get_co().len(), 1);
         }
@@ -524,10 +724,14 @@ mod tests {
         let mut mesh = get_mesh_with_six_nodes();
         let _new_cells = add_two_seg2_cells(&mut mesh).unwrap();
         let group_node_ids = vec![0, 2, 4];
-        assert_eq!(mesh.create_node_group("GROUP1", &group_node_ids), Ok(()));
-        assert!(mesh.create_node_group("GROUP_NOT_POSSIBLE", &vec![1000]).is_err());
-        let gma = &mesh.gma.clone();
-        let actual_node_ids = gma.get("GROUP1").unwrap();
+        assert!(mesh.create_node_group("GROUP1", &group_node_ids).is_ok());
+        assert!(matches!(
+            mesh.create_node_group("GROUP_NOT_POSSIBLE", &[1000]),
+            Err(MeshError::UnknownNodeId(1000))
+        ));
+        assert!(mesh.gma.is_empty());
+        let gno = &mesh.gno.clone();
+        let actual_node_ids = gno.get("GROUP1").unwrap();
         assert_eq!(actual_node_ids, &group_node_ids.clone());
     }
     #[test]
@@ -535,8 +739,11 @@ mod tests {
         let mut mesh = get_mesh_with_six_nodes();
         let new_cells = add_two_seg2_cells(&mut mesh).unwrap();
         let group_cell_ids = new_cells.clone();
-        assert_eq!(mesh.create_cell_group("GROUP1", &group_cell_ids), Ok(()));
-        assert!(mesh.create_cell_group("GROUP_NOT_POSSIBLE", &vec![1000]).is_err());
+        assert!(mesh.create_cell_group("GROUP1", &group_cell_ids).is_ok());
+        assert!(matches!(
+            mesh.create_cell_group("GROUP_NOT_POSSIBLE", &[1000]),
+            Err(MeshError::UnknownCellId(1000))
+        ));
         let gma = &mesh.gma.clone();
         let actual_cell_ids = gma.get("GROUP1").unwrap();
         assert_eq!(actual_cell_ids, &new_cells.clone());
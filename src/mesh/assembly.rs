@@ -0,0 +1,406 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::mesh::coincident_nodes::{distance, SpatialGrid};
+use crate::mesh::names::{check_cell_name, check_group_name, check_node_name};
+use crate::mesh::{IdMapping, Mesh};
+use crate::mesh_enums::{GroupMergePolicy, NameClashPolicy};
+use crate::mesh_errors::MeshError;
+
+// how a mesh is appended to another one by `Mesh::merge`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeOptions {
+    // renaming of the appended nodes, cells and separate groups whose name is already used
+    pub name_clash: NameClashPolicy,
+    pub groups: GroupMergePolicy,
+    // appended nodes closer than this tolerance to an existing node are fused into it
+    pub fuse_tolerance: Option<f64>,
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        MergeOptions {
+            name_clash: NameClashPolicy::Error,
+            groups: GroupMergePolicy::Union,
+            fuse_tolerance: None,
+        }
+    }
+}
+
+// new name of `name` if it is already taken, checked by `check` so that it can be
+// written and read back
+fn resolve_clash(
+    name: &str,
+    policy: &NameClashPolicy,
+    is_taken: impl Fn(&str) -> bool,
+    duplicate: fn(String) -> MeshError,
+    check: fn(&str) -> Result<(), MeshError>,
+) -> Result<String, MeshError> {
+    if !is_taken(name) {
+        check(name)?;
+        return Ok(name.to_string());
+    }
+    let new_name = match policy {
+        NameClashPolicy::Prefix(prefix) => format!("{}{}", prefix, name),
+        NameClashPolicy::Suffix(suffix) => format!("{}{}", name, suffix),
+        NameClashPolicy::Error => return Err(duplicate(name.to_string())),
+    };
+    if is_taken(&new_name) {
+        return Err(duplicate(new_name));
+    }
+    check(&new_name)?;
+    Ok(new_name)
+}
+
+// new names of the entities of `other`, checked against the existing names and
+// against each other
+fn resolve_names(
+    names: HashMap<usize, String>,
+    policy: &NameClashPolicy,
+    existing: &HashMap<Box<str>, usize>,
+    duplicate: fn(String) -> MeshError,
+    check: fn(&str) -> Result<(), MeshError>,
+) -> Result<HashMap<usize, String>, MeshError> {
+    let mut ids: Vec<usize> = names.keys().copied().collect();
+    ids.sort();
+    let other_names: HashSet<&str> = names.values().map(|name| name.as_str()).collect();
+    let mut resolved = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    for id in ids {
+        let name = resolve_clash(
+            &names[&id],
+            policy,
+            |name| existing.contains_key(name) || taken.contains(name),
+            duplicate,
+            check,
+        )?;
+        // a renamed entity must not take the name of another appended entity
+        if name != names[&id] && other_names.contains(name.as_str()) {
+            return Err(duplicate(name));
+        }
+        taken.insert(name.clone());
+        resolved.insert(id, name);
+    }
+    Ok(resolved)
+}
+
+// names given to the groups of `other`
+fn resolve_group_names(
+    groups: &HashMap<Box<str>, Vec<usize>>,
+    options: &MergeOptions,
+    existing: &HashMap<Box<str>, Vec<usize>>,
+    duplicate: fn(String) -> MeshError,
+) -> Result<HashMap<Box<str>, String>, MeshError> {
+    let mut names: Vec<&Box<str>> = groups.keys().collect();
+    names.sort();
+    let mut resolved = HashMap::new();
+    let mut taken: HashSet<String> = HashSet::new();
+    for name in names {
+        let new_name = match options.groups {
+            GroupMergePolicy::Union => {
+                check_group_name(name)?;
+                name.to_string()
+            }
+            GroupMergePolicy::Separate => {
+                let new_name = resolve_clash(
+                    name,
+                    &options.name_clash,
+                    |name| existing.contains_key(name) || taken.contains(name),
+                    duplicate,
+                    check_group_name,
+                )?;
+                // a renamed group must not take the name of another appended group
+                if new_name != name.as_ref() && groups.contains_key(new_name.as_str()) {
+                    return Err(duplicate(new_name));
+                }
+                new_name
+            }
+        };
+        taken.insert(new_name.clone());
+        resolved.insert(name.clone(), new_name);
+    }
+    Ok(resolved)
+}
+
+fn merge_groups(
+    groups: &mut HashMap<Box<str>, Vec<usize>>,
+    other_groups: HashMap<Box<str>, Vec<usize>>,
+    names: &HashMap<Box<str>, String>,
+    ids: &HashMap<usize, usize>,
+) {
+    for (name, other_ids) in other_groups {
+        let group = groups.entry(names[&name].as_str().into()).or_default();
+        group.extend(other_ids.iter().filter_map(|id| ids.get(id)));
+        group.sort();
+        group.dedup();
+    }
+}
+
+impl Mesh {
+    // Appends the nodes, cells and groups of `other`. Ids of `other` are offset by
+    // `next_node_id` and `next_cell_id`, clashing names are resolved as set in
+    // `options`. Returns the ids given to the entities of `other`, fused nodes
+    // are given the id of the node they were fused into.
+    // Nothing is appended if an error is returned
+    pub fn merge(&mut self, other: Mesh, options: &MergeOptions) -> Result<IdMapping, MeshError> {
+        // cells of `other` must only use its own nodes
+        for cell in other.cells.values() {
+            other.check_node_ids(&cell.co)?;
+        }
+        let node_names = resolve_names(
+            other.node_names(),
+            &options.name_clash,
+            &self.nodes_name_to_id,
+            MeshError::DuplicateNodeName,
+            check_node_name,
+        )?;
+        let cell_names = resolve_names(
+            other.cell_names(),
+            &options.name_clash,
+            &self.cells_name_to_id,
+            MeshError::DuplicateCellName,
+            check_cell_name,
+        )?;
+        let gno_names =
+            resolve_group_names(&other.gno, options, &self.gno, MeshError::DuplicateNodeGroup)?;
+        let gma_names =
+            resolve_group_names(&other.gma, options, &self.gma, MeshError::DuplicateCellGroup)?;
+
+        // nodes of `other` fused into the existing nodes
+        let mut fused: HashMap<usize, usize> = HashMap::new();
+        if let Some(tol) = options.fuse_tolerance {
+            let mut grid = SpatialGrid::new(tol, self.nodes.values().chain(other.nodes.values()));
+            for (node_id, node) in self.nodes.iter() {
+                grid.insert(*node_id, node);
+            }
+            for (other_id, node) in other.nodes.iter() {
+                let nearest = grid
+                    .candidates(node)
+                    .into_iter()
+                    .map(|node_id| (node_id, distance(node, &self.nodes[&node_id])))
+                    .filter(|(_, dist)| *dist <= tol)
+                    .min_by(|(id1, dist1), (id2, dist2)| {
+                        dist1.total_cmp(dist2).then(id1.cmp(id2))
+                    });
+                if let Some((node_id, _)) = nearest {
+                    fused.insert(*other_id, node_id);
+                }
+            }
+        }
+
+        let node_offset = self.next_node_id;
+        let cell_offset = self.next_cell_id;
+        let mut mapping = IdMapping {
+            nodes: other.nodes.keys().map(|id| (*id, id + node_offset)).collect(),
+            cells: other.cells.keys().map(|id| (*id, id + cell_offset)).collect(),
+        };
+        for (other_id, node) in other.nodes {
+            let node_id = mapping.nodes[&other_id];
+            self.nodes.insert(node_id, node);
+            let name = node_names[&other_id].as_str();
+            self.nodes_name_to_id.insert(name.into(), node_id);
+            self.nodes_id_to_name.insert(node_id, name.into());
+        }
+        for (other_id, mut cell) in other.cells {
+            let cell_id = mapping.cells[&other_id];
+            for node_id in cell.co.iter_mut() {
+                *node_id = mapping.nodes[node_id];
+            }
+            self.cells.insert(cell_id, cell);
+            let name = cell_names[&other_id].as_str();
+            self.cells_name_to_id.insert(name.into(), cell_id);
+            self.cells_id_to_name.insert(cell_id, name.into());
+        }
+        merge_groups(&mut self.gno, other.gno, &gno_names, &mapping.nodes);
+        merge_groups(&mut self.gma, other.gma, &gma_names, &mapping.cells);
+        self.next_node_id = node_offset + other.next_node_id;
+        self.next_cell_id = cell_offset + other.next_cell_id;
+        self.dim = self.dim.max(other.dim);
+
+        let fused: HashMap<usize, usize> = fused
+            .into_iter()
+            .map(|(other_id, node_id)| (mapping.nodes[&other_id], node_id))
+            .collect();
+        self.fuse_nodes(&fused);
+        for node_id in mapping.nodes.values_mut() {
+            if let Some(kept_id) = fused.get(node_id) {
+                *node_id = *kept_id;
+            }
+        }
+        Ok(mapping)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::mail_writer::mail_writer;
+    use crate::mesh::assembly::MergeOptions;
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::{GroupMergePolicy, MeshFormat, NameClashPolicy};
+    use crate::mesh_errors::MeshError;
+
+    fn get_bar(x0: f64) -> Mesh {
+        let content = format!(
+            "COOR_3D\nN1 {} 0. 0.\nN2 {} 0. 0.\nFINSF\nSEG2\nM1 N1 N2\nFINSF\n\
+             GROUP_NO ENDS N1 N2\nFINSF\nGROUP_MA BAR M1\nFINSF\nFIN",
+            x0,
+            x0 + 1.
+        );
+        Mesh::create_from_parser_output(mail_parser(&content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn merge_should_refuse_name_clashes_by_default() {
+        let mut mesh = get_bar(0.);
+        assert!(matches!(
+            mesh.merge(get_bar(1.), &MergeOptions::default()),
+            Err(MeshError::DuplicateNodeName(name)) if name == "N1"
+        ));
+        assert_eq!(mesh.nodes.len(), 2);
+    }
+
+    #[test]
+    fn merge_should_offset_ids_and_rename() {
+        let mut mesh = get_bar(0.);
+        mesh.remove_cells(&[0]).unwrap();
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Suffix("0".to_string()),
+            ..MergeOptions::default()
+        };
+        let mapping = mesh.merge(get_bar(1.), &options).unwrap();
+        assert_eq!(mapping.nodes[&0], 2);
+        assert_eq!(mapping.nodes[&1], 3);
+        assert_eq!(mapping.cells[&0], 1);
+        assert_eq!(mesh.next_node_id, 4);
+        assert_eq!(mesh.next_cell_id, 2);
+        assert_eq!(mesh.cells[&1].co, vec![2, 3]);
+        assert_eq!(mesh.node_id_by_name("N10").unwrap(), 2);
+        // M1 was removed, its name is free
+        assert_eq!(mesh.cell_name(1).unwrap(), "M1");
+        // groups with the same name are merged
+        assert_eq!(mesh.get_node_group("ENDS").unwrap(), &[0, 1, 2, 3]);
+        assert_eq!(mesh.get_cell_group("BAR").unwrap(), &[1]);
+    }
+
+    #[test]
+    fn merge_should_refuse_names_which_cannot_be_read_back() {
+        let mut mesh = get_bar(0.);
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Suffix("_B".to_string()),
+            ..MergeOptions::default()
+        };
+        assert!(matches!(
+            mesh.merge(get_bar(1.), &options),
+            Err(MeshError::InvalidNodeName(name)) if name == "N1_B"
+        ));
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Prefix("B".to_string()),
+            groups: GroupMergePolicy::Separate,
+            fuse_tolerance: None,
+        };
+        let mut other = get_bar(1.);
+        other.create_cell_group("BAR-2", &[0]).unwrap();
+        assert!(matches!(
+            mesh.merge(other, &options),
+            Err(MeshError::InvalidGroupName(name)) if name == "BAR-2"
+        ));
+        assert_eq!(mesh.nodes.len(), 2);
+    }
+
+    #[test]
+    fn merge_should_refuse_dangling_node_ids() {
+        let mut mesh = get_bar(0.);
+        let mut other = get_bar(1.);
+        // node 1 is still used by cell 0
+        other.nodes.remove(&1);
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Prefix("B".to_string()),
+            ..MergeOptions::default()
+        };
+        assert!(matches!(mesh.merge(other, &options), Err(MeshError::UnknownNodeId(1))));
+        assert_eq!(mesh.nodes.len(), 2);
+        assert_eq!(mesh.cells.len(), 1);
+    }
+
+    #[test]
+    fn merged_mesh_should_be_read_back() {
+        let mut mesh = get_bar(0.);
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Prefix("B".to_string()),
+            groups: GroupMergePolicy::Separate,
+            fuse_tolerance: Some(1e-6),
+        };
+        mesh.merge(get_bar(1.), &options).unwrap();
+        let written = mail_writer(&mesh);
+        let reread = Mesh::create_from_parser_output(mail_parser(&written).unwrap()).unwrap();
+        assert_eq!(reread.nodes.len(), 3);
+        assert_eq!(reread.cells.len(), 2);
+        assert_eq!(reread.node_name(1).unwrap(), "N2");
+        assert_eq!(reread.get_cell_group("BBAR").unwrap(), &[1]);
+        assert_eq!(reread.get_node_group("BENDS").unwrap(), &[1, 2]);
+        assert!(reread.validate(MeshFormat::Mail).is_valid());
+    }
+
+    #[test]
+    fn merge_should_keep_groups_separate() {
+        let mut mesh = get_bar(0.);
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Prefix("B".to_string()),
+            groups: GroupMergePolicy::Separate,
+            fuse_tolerance: None,
+        };
+        mesh.merge(get_bar(1.), &options).unwrap();
+        assert_eq!(mesh.get_node_group("ENDS").unwrap(), &[0, 1]);
+        assert_eq!(mesh.get_node_group("BENDS").unwrap(), &[2, 3]);
+        assert_eq!(mesh.get_cell_group("BBAR").unwrap(), &[1]);
+        assert_eq!(mesh.node_name(2).unwrap(), "BN1");
+    }
+
+    #[test]
+    fn merge_should_keep_separate_groups_without_clash() {
+        // no name of this bar is used by `get_bar`
+        let get_other = || {
+            let mut other = get_bar(1.);
+            other.rename_node(0, "P1").unwrap();
+            other.rename_node(1, "P2").unwrap();
+            other.rename_cell(0, "S1").unwrap();
+            other.rename_node_group("ENDS", "TIPS").unwrap();
+            other.rename_cell_group("BAR", "B").unwrap();
+            other
+        };
+        let options = MergeOptions {
+            groups: GroupMergePolicy::Separate,
+            ..MergeOptions::default()
+        };
+        let mut mesh = get_bar(0.);
+        mesh.merge(get_other(), &options).unwrap();
+        assert_eq!(mesh.get_node_group("TIPS").unwrap(), &[2, 3]);
+        assert_eq!(mesh.get_cell_group("B").unwrap(), &[1]);
+
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Prefix("X".to_string()),
+            ..options
+        };
+        let mut mesh = get_bar(0.);
+        mesh.merge(get_other(), &options).unwrap();
+        assert_eq!(mesh.get_cell_group("B").unwrap(), &[1]);
+        assert!(mesh.get_cell_group("XB").is_err());
+    }
+
+    #[test]
+    fn merge_should_fuse_interface_nodes() {
+        let mut mesh = get_bar(0.);
+        let options = MergeOptions {
+            name_clash: NameClashPolicy::Prefix("B".to_string()),
+            groups: GroupMergePolicy::Union,
+            fuse_tolerance: Some(1e-6),
+        };
+        let mapping = mesh.merge(get_bar(1.), &options).unwrap();
+        assert_eq!(mapping.nodes[&0], 1);
+        assert_eq!(mesh.nodes.len(), 3);
+        assert_eq!(mesh.cells[&1].co, vec![1, 3]);
+        assert_eq!(mesh.get_node_group("ENDS").unwrap(), &[0, 1, 3]);
+        assert!(mesh.node_id_by_name("BN1").is_err());
+    }
+}
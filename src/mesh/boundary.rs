@@ -0,0 +1,225 @@
+use std::collections::HashMap;
+
+use crate::cell_topology::LocalEntity;
+use crate::mesh::Mesh;
+use crate::mesh_enums::CellType;
+use crate::mesh_errors::MeshError;
+
+// sub-entity of a cell, with global node ids
+struct BoundaryEntity {
+    ty: CellType,
+    co: Vec<usize>,
+}
+
+// Sub-entities used by exactly one of the cells of topological dimension `dim`,
+// in the order of the cells and of their local entities. Entities are identified
+// by their corner nodes
+fn entities_used_once(
+    mesh: &Mesh,
+    dim: usize,
+    sub_entities: fn(&CellType) -> Vec<LocalEntity>,
+) -> Vec<BoundaryEntity> {
+    let mut cell_ids: Vec<&usize> = mesh
+        .cells
+        .iter()
+        .filter(|(_, cell)| cell.ty.topological_dimension() == dim)
+        .map(|(cell_id, _)| cell_id)
+        .collect();
+    cell_ids.sort();
+
+    let mut entities: Vec<(Vec<usize>, BoundaryEntity)> = vec![];
+    let mut counts: HashMap<Vec<usize>, usize> = HashMap::new();
+    for cell_id in cell_ids {
+        let cell = &mesh.cells[cell_id];
+        for local in sub_entities(&cell.ty) {
+            let co: Vec<usize> = local.nodes.iter().map(|i| cell.co[*i]).collect();
+            let mut key = co[..local.ty.get_nb_of_corner_nodes()].to_vec();
+            key.sort();
+            *counts.entry(key.clone()).or_default() += 1;
+            entities.push((key, BoundaryEntity { ty: local.ty, co }));
+        }
+    }
+    entities
+        .into_iter()
+        .filter(|(key, _)| counts[key] == 1)
+        .map(|(_, entity)| entity)
+        .collect()
+}
+
+impl Mesh {
+    // creates the entities as new cells, optionally stored in a new group of cells
+    fn add_boundary_cells(
+        &mut self,
+        entities: Vec<BoundaryEntity>,
+        group: Option<&str>,
+    ) -> Result<Vec<usize>, MeshError> {
+        if let Some(name) = group {
+            if self.gma.contains_key(name) {
+                return Err(MeshError::DuplicateCellGroup(name.to_string()));
+            }
+        }
+        let mut cell_ids = vec![];
+        for entity in entities {
+            cell_ids.push(self.add_a_cell(entity.ty, &entity.co)?);
+        }
+        if let Some(name) = group {
+            self.create_cell_group(name, &cell_ids)?;
+        }
+        Ok(cell_ids)
+    }
+
+    // Creates TRIA* and QUAD* cells on the faces used by a single volume cell, with
+    // their normal pointing outwards. Faces of quadratic cells are quadratic.
+    // Returns the ids of the created cells, stored in the group `group` if given
+    pub fn extract_skin(&mut self, group: Option<&str>) -> Result<Vec<usize>, MeshError> {
+        let faces = entities_used_once(self, 3, CellType::faces);
+        self.add_boundary_cells(faces, group)
+    }
+
+    // Creates SEG2 and SEG3 cells on the edges used by a single TRIA* or QUAD* cell,
+    // oriented as the edges of their cell. Free edges inside a shell reveal cracks or holes.
+    // Returns the ids of the created cells, stored in the group `group` if given
+    pub fn extract_free_edges(&mut self, group: Option<&str>) -> Result<Vec<usize>, MeshError> {
+        let edges = entities_used_once(self, 2, CellType::edges);
+        self.add_boundary_cells(edges, group)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::CellType;
+    use crate::mesh::Mesh;
+    use crate::mesh_errors::MeshError;
+
+    // two HEXA8 cells sharing the face x = 1
+    fn get_two_hexa() -> Mesh {
+        let content = r#"COOR_3D
+N1 0. 0. 0.
+N2 1. 0. 0.
+N3 1. 1. 0.
+N4 0. 1. 0.
+N5 0. 0. 1.
+N6 1. 0. 1.
+N7 1. 1. 1.
+N8 0. 1. 1.
+N9 2. 0. 0.
+N10 2. 1. 0.
+N11 2. 0. 1.
+N12 2. 1. 1.
+FINSF
+HEXA8
+M1 N1 N2 N3 N4 N5 N6 N7 N8
+M2 N2 N9 N10 N3 N6 N11 N12 N7
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn extract_skin_should_skip_shared_faces() {
+        let mut mesh = get_two_hexa();
+        let skin = mesh.extract_skin(Some("SKIN")).unwrap();
+        assert_eq!(skin.len(), 10);
+        assert_eq!(mesh.get_cell_group("SKIN").unwrap(), skin.as_slice());
+        assert!(skin
+            .iter()
+            .all(|cell_id| mesh.cells[cell_id].ty == CellType::QUAD4));
+        // the bottom face of M1 is seen from below
+        assert_eq!(mesh.cells[&skin[0]].co, vec![0, 3, 2, 1]);
+        // shared face N2 N3 N7 N6 is not in the skin
+        assert!(skin.iter().all(|cell_id| {
+            let mut co = mesh.cells[cell_id].co.clone();
+            co.sort();
+            co != vec![1, 2, 5, 6]
+        }));
+    }
+
+    #[test]
+    fn extract_skin_should_handle_quadratic_cells() {
+        let content = r#"COOR_3D
+N1 0. 1. 0.
+N2 0. 0. 1.
+N3 0. 0. 0.
+N4 1. 0. 0.
+N5 0. 0.5 0.5
+N6 0. 0. 0.5
+N7 0. 0.5 0.
+N8 0.5 0.5 0.
+N9 0.5 0. 0.5
+N10 0.5 0. 0.
+FINSF
+TETRA10
+M1 N1 N2 N3 N4 N5 N6 N7 N8 N9 N10
+FINSF
+FIN"#;
+        let mut mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        let skin = mesh.extract_skin(None).unwrap();
+        assert_eq!(skin.len(), 4);
+        assert_eq!(mesh.cells[&skin[0]].ty, CellType::TRIA6);
+        assert_eq!(mesh.cells[&skin[0]].co, vec![0, 2, 1, 6, 5, 4]);
+        assert!(mesh.gma.is_empty());
+    }
+
+    #[test]
+    fn extract_free_edges_should_find_plate_edges_and_cracks() {
+        let content = r#"COOR_3D
+N1 0. 0. 0.
+N2 1. 0. 0.
+N3 1. 1. 0.
+N4 0. 1. 0.
+N5 2. 0. 0.
+N6 2. 1. 0.
+N7 1. 0. 0.
+FINSF
+QUAD4
+M1 N1 N2 N3 N4
+M2 N2 N5 N6 N3
+FINSF
+FIN"#;
+        let mut mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        let edges = mesh.extract_free_edges(Some("EDGES")).unwrap();
+        assert_eq!(edges.len(), 6);
+        assert_eq!(mesh.cells[&edges[0]].ty, CellType::SEG2);
+        assert_eq!(mesh.cells[&edges[0]].co, vec![0, 1]);
+        assert_eq!(mesh.get_cell_group("EDGES").unwrap(), edges.as_slice());
+
+        // the second cell no longer uses N2, the edges along x = 1 are free
+        mesh.remove_cells(&edges).unwrap();
+        mesh.edit_cell(1, &[6, 4, 5, 2], CellType::QUAD4).unwrap();
+        assert_eq!(mesh.extract_free_edges(None).unwrap().len(), 8);
+    }
+
+    #[test]
+    fn extract_free_edges_should_handle_quadratic_cells() {
+        let content = r#"COOR_2D
+N1 0. 0.
+N2 1. 0.
+N3 0. 1.
+N4 0.5 0.
+N5 0.5 0.5
+N6 0. 0.5
+FINSF
+TRIA6
+M1 N1 N2 N3 N4 N5 N6
+FINSF
+FIN"#;
+        let mut mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        let edges = mesh.extract_free_edges(None).unwrap();
+        assert_eq!(edges.len(), 3);
+        assert_eq!(mesh.cells[&edges[2]].ty, CellType::SEG3);
+        assert_eq!(mesh.cells[&edges[2]].co, vec![2, 0, 5]);
+    }
+
+    #[test]
+    fn extract_skin_should_not_overwrite_a_group() {
+        let mut mesh = get_two_hexa();
+        mesh.create_cell_group("SKIN", &[0]).unwrap();
+        assert!(matches!(
+            mesh.extract_skin(Some("SKIN")),
+            Err(MeshError::DuplicateCellGroup(name)) if name == "SKIN"
+        ));
+        assert_eq!(mesh.cells.len(), 2);
+    }
+}
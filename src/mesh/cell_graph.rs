@@ -0,0 +1,198 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::mesh::Mesh;
+use crate::mesh_errors::MeshError;
+
+// prefix of the groups of cells created by `group_connected_components`
+const PART_PREFIX: &str = "PART_";
+
+impl Mesh {
+    // Keys identifying the entities through which a cell touches its neighbours:
+    // its facets (faces of volumes, edges of shells, ends of segments) and the
+    // cell itself, so that a skin or a beam cell is a neighbour of the cell it lies on.
+    // Keys are sorted corner node ids
+    fn contact_keys(&self, cell_id: usize) -> Vec<Vec<usize>> {
+        let cell = &self.cells[&cell_id];
+        let corners = |nodes: &[usize], nb_corners: usize| {
+            let mut key: Vec<usize> = nodes[..nb_corners].iter().map(|i| cell.co[*i]).collect();
+            key.sort();
+            key
+        };
+        let facets = match cell.ty.topological_dimension() {
+            3 => cell.ty.faces(),
+            2 => cell.ty.edges(),
+            _ => vec![],
+        };
+        let mut keys: Vec<Vec<usize>> = facets
+            .iter()
+            .map(|facet| corners(facet.nodes, facet.ty.get_nb_of_corner_nodes()))
+            .collect();
+        if cell.ty.topological_dimension() == 1 {
+            keys.extend(cell.ty.corner_node_indices().map(|i| vec![cell.co[i]]));
+        }
+        let all: Vec<usize> = (0..cell.ty.get_nb_of_corner_nodes()).collect();
+        keys.push(corners(&all, all.len()));
+        keys
+    }
+
+    // Neighbours of every cell: volumes sharing a face, shells sharing an edge,
+    // segments sharing an end, and cells lying on a face, an edge or an end of
+    // another cell. Neighbour lists are sorted
+    pub fn cell_neighbours(&self) -> HashMap<usize, Vec<usize>> {
+        let mut cell_ids: Vec<usize> = self.cells.keys().copied().collect();
+        cell_ids.sort();
+        let mut cells_by_key: HashMap<Vec<usize>, Vec<usize>> = HashMap::new();
+        for cell_id in cell_ids.iter() {
+            for key in self.contact_keys(*cell_id) {
+                cells_by_key.entry(key).or_default().push(*cell_id);
+            }
+        }
+        let mut neighbours: HashMap<usize, Vec<usize>> =
+            cell_ids.iter().map(|cell_id| (*cell_id, vec![])).collect();
+        for cells in cells_by_key.values() {
+            for cell_id in cells {
+                let cell_neighbours = neighbours.get_mut(cell_id).unwrap();
+                cell_neighbours.extend(cells.iter().filter(|other| *other != cell_id));
+            }
+        }
+        for cell_neighbours in neighbours.values_mut() {
+            cell_neighbours.sort();
+            cell_neighbours.dedup();
+        }
+        neighbours
+    }
+
+    // sets of connected cells, sorted, in order of their smallest cell id
+    pub fn connected_components(&self) -> Vec<Vec<usize>> {
+        let neighbours = self.cell_neighbours();
+        let mut cell_ids: Vec<usize> = self.cells.keys().copied().collect();
+        cell_ids.sort();
+        let mut component_of: HashMap<usize, usize> = HashMap::new();
+        let mut components = vec![];
+        for cell_id in cell_ids {
+            if component_of.contains_key(&cell_id) {
+                continue;
+            }
+            let mut component = vec![];
+            let mut queue = VecDeque::from([cell_id]);
+            component_of.insert(cell_id, components.len());
+            while let Some(current) = queue.pop_front() {
+                component.push(current);
+                for neighbour in neighbours[&current].iter() {
+                    if !component_of.contains_key(neighbour) {
+                        component_of.insert(*neighbour, components.len());
+                        queue.push_back(*neighbour);
+                    }
+                }
+            }
+            component.sort();
+            components.push(component);
+        }
+        components
+    }
+
+    // stores every connected component in a group of cells PART_1, PART_2, ...
+    // Nothing is created if one of these groups already exists
+    pub fn group_connected_components(&mut self) -> Result<Vec<Vec<usize>>, MeshError> {
+        let components = self.connected_components();
+        let names: Vec<String> = (1..=components.len())
+            .map(|part| format!("{}{}", PART_PREFIX, part))
+            .collect();
+        if let Some(name) = names
+            .iter()
+            .find(|name| self.gma.contains_key(name.as_str()))
+        {
+            return Err(MeshError::DuplicateCellGroup(name.to_string()));
+        }
+        for (name, component) in names.iter().zip(components.iter()) {
+            self.create_cell_group(name, component)?;
+        }
+        Ok(components)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::mesh::Mesh;
+    use crate::mesh_errors::MeshError;
+
+    // two TETRA4 sharing a face, a separate TETRA4 touching them by a node only,
+    // a TRIA3 on a face of the first one and a floating SEG2
+    fn get_mesh_with_parts() -> Mesh {
+        let content = r#"COOR_3D
+N1 0. 1. 0.
+N2 0. 0. 1.
+N3 0. 0. 0.
+N4 1. 0. 0.
+N5 -1. 0. 0.
+N6 0. 2. 0.
+N7 0. 1. 1.
+N8 1. 1. 0.
+N9 5. 5. 5.
+N10 6. 5. 5.
+FINSF
+TETRA4
+M1 N1 N2 N3 N4
+M2 N1 N3 N2 N5
+M3 N1 N6 N7 N8
+FINSF
+TRIA3
+M4 N2 N3 N4
+FINSF
+SEG2
+M5 N9 N10
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn cell_neighbours_should_use_shared_faces() {
+        let mesh = get_mesh_with_parts();
+        let neighbours = mesh.cell_neighbours();
+        assert_eq!(neighbours[&0], vec![1, 3]);
+        assert_eq!(neighbours[&1], vec![0]);
+        // sharing a node is not enough
+        assert!(neighbours[&2].is_empty());
+        assert_eq!(neighbours[&3], vec![0]);
+        assert!(neighbours[&4].is_empty());
+    }
+
+    #[test]
+    fn shells_should_be_connected_by_edges() {
+        let content = r#"COOR_2D
+N1 0. 0.
+N2 1. 0.
+N3 1. 1.
+N4 0. 1.
+N5 2. 1.
+FINSF
+TRIA3
+M1 N1 N2 N3
+M2 N1 N3 N4
+M3 N3 N2 N5
+FINSF
+SEG2
+M4 N4 N1
+FINSF
+FIN"#;
+        let mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        assert_eq!(mesh.connected_components(), vec![vec![0, 1, 2, 3]]);
+        assert_eq!(mesh.cell_neighbours()[&3], vec![1]);
+    }
+
+    #[test]
+    fn connected_components_should_be_grouped() {
+        let mut mesh = get_mesh_with_parts();
+        let components = mesh.group_connected_components().unwrap();
+        assert_eq!(components, vec![vec![0, 1, 3], vec![2], vec![4]]);
+        assert_eq!(mesh.get_cell_group("PART_1").unwrap(), &[0, 1, 3]);
+        assert_eq!(mesh.get_cell_group("PART_3").unwrap(), &[4]);
+        assert!(matches!(
+            mesh.group_connected_components(),
+            Err(MeshError::DuplicateCellGroup(name)) if name == "PART_1"
+        ));
+    }
+}
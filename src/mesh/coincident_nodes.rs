@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use crate::mesh::Mesh;
+use crate::node::Node;
+
+// largest number of buckets along an axis of the bounding box
+const MAX_BUCKETS_PER_AXIS: f64 = 1e6;
+
+// Buckets of node ids on a regular grid, used to find the nodes close to a point
+// without comparing every pair of nodes
+pub(crate) struct SpatialGrid {
+    origin: [f64; 3],
+    size: f64,
+    buckets: HashMap<(i64, i64, i64), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    // Buckets are at least as large as the search tolerance, and not too small compared
+    // to the bounding box of the nodes which will be inserted or searched, so that
+    // their keys stay small
+    pub(crate) fn new<'n>(tol: f64, nodes: impl IntoIterator<Item = &'n Node>) -> Self {
+        let mut min = [f64::INFINITY; 3];
+        let mut max = [f64::NEG_INFINITY; 3];
+        for node in nodes {
+            for (k, coord) in [node.x, node.y, node.z].into_iter().enumerate() {
+                min[k] = min[k].min(coord);
+                max[k] = max[k].max(coord);
+            }
+        }
+        let extent = (0..3).map(|k| max[k] - min[k]).fold(0., f64::max);
+        let size = tol.max(extent / MAX_BUCKETS_PER_AXIS);
+        SpatialGrid {
+            origin: min.map(|coord| if coord.is_finite() { coord } else { 0. }),
+            size: if size > 0. { size } else { 1. },
+            buckets: HashMap::new(),
+        }
+    }
+
+    fn key(&self, node: &Node) -> (i64, i64, i64) {
+        (
+            ((node.x - self.origin[0]) / self.size).floor() as i64,
+            ((node.y - self.origin[1]) / self.size).floor() as i64,
+            ((node.z - self.origin[2]) / self.size).floor() as i64,
+        )
+    }
+
+    pub(crate) fn insert(&mut self, node_id: usize, node: &Node) {
+        self.buckets.entry(self.key(node)).or_default().push(node_id);
+    }
+
+    // ids in the buckets around the node, their distance to the node is not checked
+    pub(crate) fn candidates(&self, node: &Node) -> Vec<usize> {
+        let (i, j, k) = self.key(node);
+        let mut keys = vec![];
+        for di in -1..=1 {
+            for dj in -1..=1 {
+                for dk in -1..=1 {
+                    keys.push((i.saturating_add(di), j.saturating_add(dj), k.saturating_add(dk)));
+                }
+            }
+        }
+        keys.sort();
+        keys.dedup();
+        let mut candidates = vec![];
+        for key in keys {
+            if let Some(ids) = self.buckets.get(&key) {
+                candidates.extend(ids);
+            }
+        }
+        candidates
+    }
+}
+
+pub(crate) fn distance(node1: &Node, node2: &Node) -> f64 {
+    ((node1.x - node2.x).powi(2) + (node1.y - node2.y).powi(2) + (node1.z - node2.z).powi(2))
+        .sqrt()
+}
+
+impl Mesh {
+    // Fuses the nodes closer than `tol` (euclidean distance). Nodes are visited by
+    // increasing id, a node is fused into the nearest previously kept node, so the
+    // smallest id of each cluster survives. Cells and groups are redirected to the
+    // survivors, the names of fused nodes are forgotten.
+    // Returns the fused node ids with their survivor
+    pub fn merge_coincident_nodes(&mut self, tol: f64) -> HashMap<usize, usize> {
+        let mut node_ids: Vec<usize> = self.nodes.keys().copied().collect();
+        node_ids.sort();
+        let mut grid = SpatialGrid::new(tol, self.nodes.values());
+        let mut fused: HashMap<usize, usize> = HashMap::new();
+        for node_id in node_ids {
+            let node = &self.nodes[&node_id];
+            let nearest = grid
+                .candidates(node)
+                .into_iter()
+                .map(|kept_id| (kept_id, distance(node, &self.nodes[&kept_id])))
+                .filter(|(_, dist)| *dist <= tol)
+                .min_by(|(id1, dist1), (id2, dist2)| {
+                    dist1.total_cmp(dist2).then(id1.cmp(id2))
+                });
+            match nearest {
+                Some((kept_id, _)) => {
+                    fused.insert(node_id, kept_id);
+                }
+                None => grid.insert(node_id, node),
+            }
+        }
+        self.fuse_nodes(&fused);
+        fused
+    }
+
+    // Redirects every reference to a fused node to its survivor and removes fused nodes,
+    // their names are forgotten
+    pub(crate) fn fuse_nodes(&mut self, fused: &HashMap<usize, usize>) {
+        if fused.is_empty() {
+            return;
+        }
+        for cell in self.cells.values_mut() {
+            for node_id in cell.co.iter_mut() {
+                if let Some(kept_id) = fused.get(node_id) {
+                    *node_id = *kept_id;
+                }
+            }
+        }
+        for group in self.gno.values_mut() {
+            for node_id in group.iter_mut() {
+                if let Some(kept_id) = fused.get(node_id) {
+                    *node_id = *kept_id;
+                }
+            }
+            group.sort();
+            group.dedup();
+        }
+        let fused_ids: Vec<usize> = fused.keys().copied().collect();
+        for node_id in fused_ids.iter() {
+            self.nodes.remove(node_id);
+        }
+        self.forget_node_names(&fused_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::{CellType, Node};
+    use crate::mesh::coincident_nodes::SpatialGrid;
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::MeshFormat;
+
+    // two SEG2 parts sharing the interface node at x = 1
+    fn get_mesh_with_duplicates() -> Mesh {
+        let content = r#"COOR_3D
+A1 0. 0. 0.
+A2 1. 0. 0.
+B1 1.0000001 0. 0.
+B2 2. 0. 0.
+FINSF
+SEG2
+S1 A1 A2
+S2 B1 B2
+FINSF
+GROUP_NO LEFT A2
+FINSF
+GROUP_NO RIGHT B1 B2
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn merge_coincident_nodes_should_fuse_close_nodes() {
+        let mut mesh = get_mesh_with_duplicates();
+        let fused = mesh.merge_coincident_nodes(1e-6);
+        assert_eq!(fused.len(), 1);
+        assert_eq!(fused[&2], 1);
+        assert_eq!(mesh.nodes.len(), 3);
+        assert_eq!(mesh.cells[&1].co, vec![1, 3]);
+        assert_eq!(mesh.get_node_group("LEFT").unwrap(), &[1]);
+        assert_eq!(mesh.get_node_group("RIGHT").unwrap(), &[1, 3]);
+        // the name of the fused node is free
+        assert!(mesh.node_id_by_name("B1").is_err());
+        assert_eq!(mesh.node_name(1).unwrap(), "A2");
+        mesh.rename_node(3, "B1").unwrap();
+        assert!(mesh.validate(MeshFormat::Mail).is_valid());
+    }
+
+    #[test]
+    fn merge_coincident_nodes_should_respect_tolerance() {
+        let mut mesh = get_mesh_with_duplicates();
+        assert!(mesh.merge_coincident_nodes(1e-8).is_empty());
+        assert_eq!(mesh.nodes.len(), 4);
+        let fused = mesh.merge_coincident_nodes(1.5);
+        // fused nodes do not attract other nodes, B2 is 2. away from A1
+        assert_eq!(fused[&1], 0);
+        assert_eq!(fused[&2], 0);
+        assert_eq!(fused.get(&3), None);
+        assert_eq!(mesh.nodes.len(), 2);
+    }
+
+    #[test]
+    fn merge_coincident_nodes_should_accept_tiny_tolerances() {
+        let mut mesh = Mesh::new();
+        for x in [1e5, 1e5, 1e5 + 1.] {
+            mesh.add_a_node(Node { x, y: -1e5, z: 0. }, None).unwrap();
+        }
+        // buckets are not smaller than a millionth of the bounding box
+        let grid = SpatialGrid::new(1e-15, mesh.nodes.values());
+        assert_eq!(grid.size, 1e-6);
+        assert_eq!(grid.key(&mesh.nodes[&2]), (1000000, 0, 0));
+        let fused = mesh.merge_coincident_nodes(1e-15);
+        assert_eq!(fused.len(), 1);
+        assert_eq!(fused[&1], 0);
+        assert_eq!(mesh.nodes.len(), 2);
+    }
+
+    #[test]
+    fn merge_coincident_nodes_should_keep_the_nearest_survivor() {
+        let mut mesh = Mesh::new();
+        for x in [0., 0.3, 0.4, 0.35] {
+            mesh.add_a_node(Node { x, y: 0., z: 0. }, None).unwrap();
+        }
+        mesh.add_a_cell(CellType::SEG2, &[0, 3]).unwrap();
+        let fused = mesh.merge_coincident_nodes(0.12);
+        assert_eq!(fused.len(), 2);
+        assert_eq!(fused[&2], 1);
+        assert_eq!(fused[&3], 1);
+        assert_eq!(mesh.cells[&0].co, vec![0, 1]);
+        assert!(mesh.node_id_by_name("N4").is_err());
+        assert_eq!(mesh.node_id_by_name("N2").unwrap(), 1);
+    }
+}
@@ -0,0 +1,244 @@
+use crate::mesh::geometry::{cross, dot, norm};
+use crate::mesh::Mesh;
+use crate::mesh_enums::{CellType, DefectKind};
+use crate::mesh_errors::MeshError;
+use crate::shape_functions::{integration_points, reference_coordinates, ShapeFunctions};
+
+// measures below this fraction of the cell size to the power of its dimension are zero
+const RELATIVE_TOLERANCE: f64 = 1e-10;
+
+// a defect found in a cell
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellDefect {
+    pub cell_id: usize,
+    pub kind: DefectKind,
+}
+
+// node ids appearing more than once in the connectivity, in order of first repetition
+fn repeated_nodes(co: &[usize]) -> Vec<usize> {
+    let mut repeated = vec![];
+    for (i, node_id) in co.iter().enumerate() {
+        if co[..i].contains(node_id) && !repeated.contains(node_id) {
+            repeated.push(*node_id);
+        }
+    }
+    repeated
+}
+
+// largest distance between two corners of the cell
+fn cell_size(cell_type: &CellType, coords: &[[f64; 3]]) -> f64 {
+    let corners = &coords[..cell_type.get_nb_of_corner_nodes()];
+    let mut size: f64 = 0.;
+    for a in corners {
+        for b in corners {
+            size = size.max(norm([b[0] - a[0], b[1] - a[1], b[2] - a[2]]));
+        }
+    }
+    size
+}
+
+// Reference points where the orientation of the cell is checked: corners and integration
+// points. The apex of pyramids is skipped as their jacobian vanishes there
+fn check_points(cell_type: &CellType) -> Vec<[f64; 3]> {
+    let mut points: Vec<[f64; 3]> = reference_coordinates(cell_type)
+        [cell_type.corner_node_indices()]
+    .iter()
+    .filter(|xi| !(cell_type.linear_type() == CellType::PYRAM5 && xi[2] == 1.))
+    .copied()
+    .collect();
+    points.extend(integration_points(cell_type).into_iter().map(|(xi, _)| xi));
+    points
+}
+
+impl Mesh {
+    // defects of a cell, see `DefectKind`
+    pub fn cell_defects(&self, cell_id: usize) -> Result<Vec<DefectKind>, MeshError> {
+        let coords = self.cell_coordinates(cell_id)?;
+        let cell = &self.cells[&cell_id];
+        let cell_type = &cell.ty;
+        let dim = cell_type.topological_dimension();
+        let mut defects = vec![];
+        let repeated = repeated_nodes(&cell.co);
+        if !repeated.is_empty() {
+            defects.push(DefectKind::RepeatedNodes(repeated));
+        }
+        if dim == 0 {
+            return Ok(defects);
+        }
+        let tolerance = RELATIVE_TOLERANCE * cell_size(cell_type, &coords).powi(dim as i32);
+        if self.cell_measure(cell_id)?.abs() <= tolerance {
+            defects.push(DefectKind::ZeroMeasure);
+        }
+        let shape_functions = ShapeFunctions::new(cell_type);
+        // surface cells of a 2D mesh are oriented along z, other ones along their normal
+        // at the center, so that only folded surface cells are inverted
+        let normal = match (self.dim, dim) {
+            (_, 1) => return Ok(defects),
+            (2, 2) => [0., 0., 1.],
+            _ => {
+                let corners = &reference_coordinates(cell_type)[cell_type.corner_node_indices()];
+                let mut center = [0.; 3];
+                for corner in corners {
+                    for k in 0..3 {
+                        center[k] += corner[k] / corners.len() as f64;
+                    }
+                }
+                let jacobian = shape_functions.jacobian(&coords, center);
+                cross(jacobian[0], jacobian[1])
+            }
+        };
+        let inverted = check_points(cell_type).into_iter().any(|xi| {
+            let jacobian = shape_functions.jacobian(&coords, xi);
+            let signed_measure = match dim {
+                2 => {
+                    dot(cross(jacobian[0], jacobian[1]), normal)
+                        / norm(normal).max(f64::MIN_POSITIVE)
+                }
+                _ => dot(cross(jacobian[0], jacobian[1]), jacobian[2]),
+            };
+            signed_measure < -tolerance
+        });
+        if inverted {
+            defects.push(DefectKind::NegativeJacobian);
+        }
+        Ok(defects)
+    }
+
+    // Inverted and degenerate cells, sorted by cell id. A cell with several defects
+    // appears once per defect
+    pub fn find_defective_cells(&self) -> Result<Vec<CellDefect>, MeshError> {
+        let mut cell_ids: Vec<usize> = self.cells.keys().copied().collect();
+        cell_ids.sort();
+        let mut defects = vec![];
+        for cell_id in cell_ids {
+            for kind in self.cell_defects(cell_id)? {
+                defects.push(CellDefect { cell_id, kind });
+            }
+        }
+        Ok(defects)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::{CellType, Node};
+    use crate::mesh::defects::CellDefect;
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::DefectKind;
+    use crate::mesh_errors::MeshError;
+
+    fn add_nodes(mesh: &mut Mesh, points: &[[f64; 3]]) -> Vec<usize> {
+        points
+            .iter()
+            .map(|[x, y, z]| {
+                mesh.add_a_node(
+                    Node {
+                        x: *x,
+                        y: *y,
+                        z: *z,
+                    },
+                    None,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    const UNIT_CUBE: [[f64; 3]; 8] = [
+        [0., 0., 0.],
+        [1., 0., 0.],
+        [1., 1., 0.],
+        [0., 1., 0.],
+        [0., 0., 1.],
+        [1., 0., 1.],
+        [1., 1., 1.],
+        [0., 1., 1.],
+    ];
+
+    #[test]
+    fn valid_cells_should_have_no_defect() {
+        let mut mesh = Mesh::new();
+        let nodes = add_nodes(&mut mesh, &UNIT_CUBE);
+        mesh.add_a_cell(CellType::HEXA8, &nodes).unwrap();
+        mesh.add_a_cell(CellType::QUAD4, &nodes[..4]).unwrap();
+        mesh.add_a_cell(CellType::PYRAM5, &[0, 1, 2, 3, 6]).unwrap();
+        mesh.add_a_cell(CellType::SEG2, &[0, 6]).unwrap();
+        mesh.add_a_cell(CellType::POI1, &[0]).unwrap();
+        assert_eq!(mesh.find_defective_cells().unwrap(), vec![]);
+    }
+
+    #[test]
+    fn inverted_and_degenerate_cells_should_be_found() {
+        let mut mesh = Mesh::new();
+        let nodes = add_nodes(&mut mesh, &UNIT_CUBE);
+        // bottom and top faces swapped
+        let inverted = mesh
+            .add_a_cell(CellType::HEXA8, &[4, 5, 6, 7, 0, 1, 2, 3])
+            .unwrap();
+        let collapsed = mesh.add_a_cell(CellType::QUAD4, &[0, 1, 1, 2]).unwrap();
+        let flat = mesh.add_a_cell(CellType::TETRA4, &[0, 1, 2, 3]).unwrap();
+        let point = mesh
+            .add_a_cell(CellType::SEG2, &[nodes[7], nodes[7]])
+            .unwrap();
+        assert_eq!(
+            mesh.find_defective_cells().unwrap(),
+            vec![
+                CellDefect {
+                    cell_id: inverted,
+                    kind: DefectKind::NegativeJacobian
+                },
+                CellDefect {
+                    cell_id: collapsed,
+                    kind: DefectKind::RepeatedNodes(vec![1])
+                },
+                CellDefect {
+                    cell_id: flat,
+                    kind: DefectKind::ZeroMeasure
+                },
+                CellDefect {
+                    cell_id: point,
+                    kind: DefectKind::RepeatedNodes(vec![7])
+                },
+                CellDefect {
+                    cell_id: point,
+                    kind: DefectKind::ZeroMeasure
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn folded_surface_cells_should_be_inverted() {
+        let mut mesh = Mesh::new();
+        // mid-node of the first edge pushed beyond the opposite corner
+        let nodes = add_nodes(
+            &mut mesh,
+            &[
+                [0., 0., 0.],
+                [1., 0., 0.],
+                [0., 1., 0.],
+                [0.5, 1.5, 0.],
+                [0.5, 0.5, 0.],
+                [0., 0.5, 0.],
+            ],
+        );
+        let folded = mesh.add_a_cell(CellType::TRIA6, &nodes).unwrap();
+        assert_eq!(
+            mesh.cell_defects(folded).unwrap(),
+            vec![DefectKind::NegativeJacobian]
+        );
+        // clockwise triangles are inverted in 2D meshes only
+        let clockwise = mesh.add_a_cell(CellType::TRIA3, &[0, 2, 1]).unwrap();
+        assert_eq!(mesh.cell_defects(clockwise).unwrap(), vec![]);
+        mesh.dim = 2;
+        assert_eq!(
+            mesh.cell_defects(clockwise).unwrap(),
+            vec![DefectKind::NegativeJacobian]
+        );
+        assert!(matches!(
+            mesh.cell_defects(10),
+            Err(MeshError::UnknownCellId(10))
+        ));
+    }
+}
@@ -0,0 +1,178 @@
+use std::collections::HashMap;
+
+use crate::mesh::{IdMapping, Mesh};
+use crate::mesh_errors::MeshError;
+
+// restriction of the groups to the extracted ids, renumbered
+fn restrict_groups(
+    groups: &HashMap<Box<str>, Vec<usize>>,
+    ids: &HashMap<usize, usize>,
+) -> HashMap<Box<str>, Vec<usize>> {
+    groups
+        .iter()
+        .filter_map(|(name, group)| {
+            let mut restricted: Vec<usize> =
+                group.iter().filter_map(|id| ids.get(id)).copied().collect();
+            restricted.sort();
+            if restricted.is_empty() {
+                return None;
+            }
+            Some((name.clone(), restricted))
+        })
+        .collect()
+}
+
+fn inverse(mapping: &HashMap<usize, usize>) -> HashMap<usize, usize> {
+    mapping.iter().map(|(id, new_id)| (*new_id, *id)).collect()
+}
+
+impl Mesh {
+    // New mesh made of the given cells and of the nodes they use, numbered 0..n in the
+    // order of the parent ids. Names are kept and groups are restricted to the extracted
+    // entities, groups which would be empty are not kept.
+    // Returns the sub-mesh and the parent ids of its nodes and cells (sub-mesh id -> parent id)
+    pub fn extract_cells(&self, cell_ids: &[usize]) -> Result<(Mesh, IdMapping), MeshError> {
+        self.check_cell_ids(cell_ids)?;
+        let mut cell_ids = cell_ids.to_vec();
+        cell_ids.sort();
+        cell_ids.dedup();
+        let mut node_ids: Vec<usize> = cell_ids
+            .iter()
+            .flat_map(|cell_id| self.cells[cell_id].co.iter().copied())
+            .collect();
+        node_ids.sort();
+        node_ids.dedup();
+        self.check_node_ids(&node_ids)?;
+
+        // parent id -> sub-mesh id
+        let nodes: HashMap<usize, usize> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(new_id, node_id)| (*node_id, new_id))
+            .collect();
+        let cells: HashMap<usize, usize> = cell_ids
+            .iter()
+            .enumerate()
+            .map(|(new_id, cell_id)| (*cell_id, new_id))
+            .collect();
+
+        let mut mesh = Mesh::new();
+        mesh.dim = self.dim;
+        mesh.naming = self.naming.clone();
+        for node_id in node_ids.iter() {
+            let new_id = nodes[node_id];
+            mesh.nodes.insert(new_id, self.nodes[node_id].clone());
+            if let Some(name) = self.nodes_id_to_name.get(node_id) {
+                mesh.nodes_name_to_id.insert(name.clone(), new_id);
+                mesh.nodes_id_to_name.insert(new_id, name.clone());
+            }
+        }
+        for cell_id in cell_ids.iter() {
+            let new_id = cells[cell_id];
+            let mut cell = self.cells[cell_id].clone();
+            for node_id in cell.co.iter_mut() {
+                *node_id = nodes[node_id];
+            }
+            mesh.cells.insert(new_id, cell);
+            if let Some(name) = self.cells_id_to_name.get(cell_id) {
+                mesh.cells_name_to_id.insert(name.clone(), new_id);
+                mesh.cells_id_to_name.insert(new_id, name.clone());
+            }
+        }
+        mesh.gno = restrict_groups(&self.gno, &nodes);
+        mesh.gma = restrict_groups(&self.gma, &cells);
+        mesh.next_node_id = node_ids.len();
+        mesh.next_cell_id = cell_ids.len();
+        let parent_ids = IdMapping {
+            nodes: inverse(&nodes),
+            cells: inverse(&cells),
+        };
+        Ok((mesh, parent_ids))
+    }
+
+    // sub-mesh made of the cells of a group, see `extract_cells`
+    pub fn extract_cell_group(&self, name: &str) -> Result<(Mesh, IdMapping), MeshError> {
+        let cell_ids = self.get_cell_group(name)?.to_vec();
+        self.extract_cells(&cell_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::mesh::Mesh;
+    use crate::mesh_errors::MeshError;
+
+    fn get_mesh_with_two_parts() -> Mesh {
+        let content = r#"COOR_2D
+N1 0. 0.
+N2 1. 0.
+N3 1. 1.
+N4 0. 1.
+N5 2. 0.
+N6 2. 1.
+FINSF
+QUAD4
+LEFT1 N1 N2 N3 N4
+RIGHT1 N2 N5 N6 N3
+FINSF
+SEG2
+B1 N1 N2
+B2 N2 N5
+FINSF
+GROUP_MA RIGHT RIGHT1 B2
+FINSF
+GROUP_MA BOTTOM B1 B2
+FINSF
+GROUP_MA LEFT LEFT1
+FINSF
+GROUP_NO CORNERS N1 N5 N6
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn extract_cell_group_should_keep_used_nodes_only() {
+        let mesh = get_mesh_with_two_parts();
+        let (sub_mesh, parent_ids) = mesh.extract_cell_group("RIGHT").unwrap();
+        assert_eq!(sub_mesh.dim, 2);
+        assert_eq!(sub_mesh.nodes.len(), 4);
+        assert_eq!(sub_mesh.cells.len(), 2);
+        assert_eq!(sub_mesh.next_node_id, 4);
+        // parent nodes 1, 2, 4 and 5 become 0, 1, 2 and 3
+        assert_eq!(sub_mesh.cells[&0].co, vec![0, 2, 3, 1]);
+        assert_eq!(parent_ids.nodes[&2], 4);
+        assert_eq!(parent_ids.cells[&1], 3);
+        assert_eq!(sub_mesh.node_name(3).unwrap(), "N6");
+        assert_eq!(sub_mesh.cell_id_by_name("B2").unwrap(), 1);
+        assert_eq!(sub_mesh.nodes[&3], mesh.nodes[&5]);
+    }
+
+    #[test]
+    fn extract_cells_should_restrict_groups() {
+        let mesh = get_mesh_with_two_parts();
+        let (sub_mesh, _) = mesh.extract_cells(&[1, 3]).unwrap();
+        assert_eq!(sub_mesh.get_cell_group("RIGHT").unwrap(), &[0, 1]);
+        assert_eq!(sub_mesh.get_cell_group("BOTTOM").unwrap(), &[1]);
+        assert_eq!(sub_mesh.get_node_group("CORNERS").unwrap(), &[2, 3]);
+        assert!(matches!(
+            sub_mesh.get_cell_group("LEFT"),
+            Err(MeshError::UnknownCellGroup(_))
+        ));
+    }
+
+    #[test]
+    fn extract_unknown_cells_should_fail() {
+        let mesh = get_mesh_with_two_parts();
+        assert!(matches!(
+            mesh.extract_cells(&[0, 9]),
+            Err(MeshError::UnknownCellId(9))
+        ));
+        assert!(matches!(
+            mesh.extract_cell_group("TOP"),
+            Err(MeshError::UnknownCellGroup(name)) if name == "TOP"
+        ));
+    }
+}
@@ -0,0 +1,304 @@
+use std::collections::HashMap;
+
+use crate::mesh::Mesh;
+use crate::mesh_enums::CellType;
+use crate::mesh_errors::MeshError;
+use crate::node::Node;
+use crate::shape_functions::{integration_points, ShapeFunctions};
+
+// length, area and volume of the cells of a group, and centroid of the cells of
+// highest topological dimension
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupMeasures {
+    pub length: f64,
+    pub area: f64,
+    pub volume: f64,
+    // None for an empty group
+    pub centroid: Option<Node>,
+}
+
+pub(crate) fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [
+        a[1] * b[2] - a[2] * b[1],
+        a[2] * b[0] - a[0] * b[2],
+        a[0] * b[1] - a[1] * b[0],
+    ]
+}
+
+pub(crate) fn dot(a: [f64; 3], b: [f64; 3]) -> f64 {
+    a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+pub(crate) fn norm(a: [f64; 3]) -> f64 {
+    dot(a, a).sqrt()
+}
+
+// Measure density of the cell at a point: norm of the tangent for segments, norm of the
+// normal for surfaces and determinant of the jacobian for volumes, i.e. signed
+pub(crate) fn jacobian_measure(jacobian: &[[f64; 3]; 3], dim: usize) -> f64 {
+    match dim {
+        1 => norm(jacobian[0]),
+        2 => norm(cross(jacobian[0], jacobian[1])),
+        3 => dot(cross(jacobian[0], jacobian[1]), jacobian[2]),
+        _ => 0.,
+    }
+}
+
+// measure and first moment of a cell
+fn integrate(
+    shape_functions: &ShapeFunctions,
+    cell_type: &CellType,
+    coords: &[[f64; 3]],
+) -> (f64, [f64; 3]) {
+    let dim = cell_type.topological_dimension();
+    let mut measure = 0.;
+    let mut moment = [0.; 3];
+    for (xi, weight) in integration_points(cell_type) {
+        let jacobian = shape_functions.jacobian(coords, xi);
+        let density = jacobian_measure(&jacobian, dim) * weight;
+        let point = shape_functions.position(coords, xi);
+        measure += density;
+        for k in 0..3 {
+            moment[k] += density * point[k];
+        }
+    }
+    (measure, moment)
+}
+
+fn mean(coords: &[[f64; 3]]) -> [f64; 3] {
+    let mut point = [0.; 3];
+    for coord in coords {
+        for k in 0..3 {
+            point[k] += coord[k] / coords.len() as f64;
+        }
+    }
+    point
+}
+
+fn to_node(point: [f64; 3]) -> Node {
+    Node {
+        x: point[0],
+        y: point[1],
+        z: point[2],
+    }
+}
+
+impl Mesh {
+    // coordinates of the nodes of a cell
+    pub(crate) fn cell_coordinates(&self, cell_id: usize) -> Result<Vec<[f64; 3]>, MeshError> {
+        let cell = self
+            .cells
+            .get(&cell_id)
+            .ok_or(MeshError::UnknownCellId(cell_id))?;
+        cell.co
+            .iter()
+            .map(|node_id| match self.nodes.get(node_id) {
+                Some(node) => Ok([node.x, node.y, node.z]),
+                None => Err(MeshError::UnknownNodeId(*node_id)),
+            })
+            .collect()
+    }
+
+    // Length of segments, area of surface cells, volume of volume cells and 0 for points.
+    // Cells are integrated with their shape functions, so quadratic cells may be curved.
+    // The volume of an inverted cell is negative
+    pub fn cell_measure(&self, cell_id: usize) -> Result<f64, MeshError> {
+        let coords = self.cell_coordinates(cell_id)?;
+        let cell_type = &self.cells[&cell_id].ty;
+        if cell_type.topological_dimension() == 0 {
+            return Ok(0.);
+        }
+        Ok(integrate(&ShapeFunctions::new(cell_type), cell_type, &coords).0)
+    }
+
+    // centre of mass of a cell with a uniform density
+    pub fn cell_centroid(&self, cell_id: usize) -> Result<Node, MeshError> {
+        let coords = self.cell_coordinates(cell_id)?;
+        let cell_type = &self.cells[&cell_id].ty;
+        if cell_type.topological_dimension() == 0 {
+            return Ok(to_node(coords[0]));
+        }
+        let (measure, moment) = integrate(&ShapeFunctions::new(cell_type), cell_type, &coords);
+        if measure == 0. {
+            return Ok(to_node(mean(&coords)));
+        }
+        Ok(to_node(moment.map(|value| value / measure)))
+    }
+
+    // total length, area and volume of the cells of a group
+    pub fn group_measures(&self, name: &str) -> Result<GroupMeasures, MeshError> {
+        let cell_ids = self.get_cell_group(name)?;
+        let mut shape_functions: HashMap<CellType, ShapeFunctions> = HashMap::new();
+        let mut totals = [0.; 4];
+        // measure and first moment of the cells of each topological dimension
+        let mut moments = [[0.; 3]; 4];
+        let mut max_dim = None;
+        for cell_id in cell_ids {
+            let coords = self.cell_coordinates(*cell_id)?;
+            let cell_type = &self.cells[cell_id].ty;
+            let dim = cell_type.topological_dimension();
+            max_dim = max_dim.max(Some(dim));
+            let (measure, moment) = match dim {
+                0 => (1., coords[0]),
+                _ => {
+                    let functions = shape_functions
+                        .entry(cell_type.clone())
+                        .or_insert_with(|| ShapeFunctions::new(cell_type));
+                    integrate(functions, cell_type, &coords)
+                }
+            };
+            totals[dim] += measure;
+            for k in 0..3 {
+                moments[dim][k] += moment[k];
+            }
+        }
+        let centroid = max_dim
+            .filter(|dim| totals[*dim] != 0.)
+            .map(|dim| to_node(moments[dim].map(|value| value / totals[dim])));
+        Ok(GroupMeasures {
+            length: totals[1],
+            area: totals[2],
+            volume: totals[3],
+            centroid,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::{CellType, Node};
+    use crate::mesh::Mesh;
+    use crate::mesh_errors::MeshError;
+    use crate::shape_functions::reference_coordinates;
+
+    fn assert_close(value: f64, expected: f64) {
+        assert!(
+            (value - expected).abs() < 1e-10,
+            "{} != {}",
+            value,
+            expected
+        );
+    }
+
+    // cell of the given type on its reference element, mapped by x -> 2x + 1, y -> 3y, z -> z
+    fn get_mapped_reference_cell(cell_type: CellType) -> Mesh {
+        let mut mesh = Mesh::new();
+        let node_ids: Vec<usize> = reference_coordinates(&cell_type)
+            .into_iter()
+            .map(|[x, y, z]| {
+                let node = Node {
+                    x: 2. * x + 1.,
+                    y: 3. * y,
+                    z,
+                };
+                mesh.add_a_node(node, None).unwrap()
+            })
+            .collect();
+        mesh.add_a_cell(cell_type, &node_ids).unwrap();
+        mesh
+    }
+
+    #[test]
+    fn cell_measure_should_work_on_reference_elements() {
+        // the mapping multiplies lengths along x by 2, areas by 6 and volumes by 6
+        let expected = [
+            (CellType::POI1, 0.),
+            (CellType::SEG2, 4.),
+            (CellType::SEG3, 4.),
+            (CellType::SEG4, 4.),
+            (CellType::TRIA3, 3.),
+            (CellType::TRIA6, 3.),
+            (CellType::TRIA7, 3.),
+            (CellType::QUAD4, 24.),
+            (CellType::QUAD8, 24.),
+            (CellType::QUAD9, 24.),
+            (CellType::HEXA8, 48.),
+            (CellType::HEXA20, 48.),
+            (CellType::HEXA27, 48.),
+            (CellType::PENTA6, 6.),
+            (CellType::PENTA15, 6.),
+            (CellType::PENTA18, 6.),
+            (CellType::TETRA4, 1.),
+            (CellType::TETRA10, 1.),
+            (CellType::PYRAM5, 4.),
+            (CellType::PYRAM13, 4.),
+        ];
+        for (cell_type, measure) in expected {
+            let mesh = get_mapped_reference_cell(cell_type);
+            assert_close(mesh.cell_measure(0).unwrap(), measure);
+        }
+    }
+
+    #[test]
+    fn cell_centroid_should_work() {
+        let mesh = get_mapped_reference_cell(CellType::TETRA10);
+        let centroid = mesh.cell_centroid(0).unwrap();
+        assert_close(centroid.x, 1.5);
+        assert_close(centroid.y, 0.75);
+        assert_close(centroid.z, 0.25);
+        let mesh = get_mapped_reference_cell(CellType::PYRAM5);
+        // a quarter of the height above the base
+        assert_close(mesh.cell_centroid(0).unwrap().z, 0.25);
+        let mesh = get_mapped_reference_cell(CellType::POI1);
+        assert_eq!(
+            mesh.cell_centroid(0).unwrap(),
+            Node {
+                x: 1.,
+                y: 0.,
+                z: 0.
+            }
+        );
+    }
+
+    #[test]
+    fn curved_quadratic_cells_should_be_integrated() {
+        // the SEG3 follows the parabola y = 1 - x², which is not integrated exactly
+        let mut mesh = Mesh::new();
+        for (x, y) in [(-1., 0.), (1., 0.), (0., 1.)] {
+            mesh.add_a_node(Node { x, y, z: 0. }, None).unwrap();
+        }
+        mesh.add_a_cell(CellType::SEG3, &[0, 1, 2]).unwrap();
+        let expected = 5f64.sqrt() + (2. + 5f64.sqrt()).ln() / 2.;
+        assert!((mesh.cell_measure(0).unwrap() - expected).abs() < 1e-2 * expected);
+    }
+
+    #[test]
+    fn group_measures_should_sum_cells() {
+        let content = r#"COOR_3D
+N1 0. 0. 0.
+N2 2. 0. 0.
+N3 2. 1. 0.
+N4 0. 1. 0.
+N5 0. 0. 1.
+N6 2. 0. 1.
+N7 2. 1. 1.
+N8 0. 1. 1.
+FINSF
+HEXA8
+M1 N1 N2 N3 N4 N5 N6 N7 N8
+FINSF
+QUAD4
+M2 N1 N4 N3 N2
+FINSF
+SEG2
+M3 N1 N2
+FINSF
+GROUP_MA ALL M1 M2 M3
+FINSF
+FIN"#;
+        let mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        let measures = mesh.group_measures("ALL").unwrap();
+        assert_close(measures.volume, 2.);
+        assert_close(measures.area, 2.);
+        assert_close(measures.length, 2.);
+        let centroid = measures.centroid.unwrap();
+        assert_close(centroid.x, 1.);
+        assert_close(centroid.z, 0.5);
+        assert!(matches!(
+            mesh.group_measures("NONE"),
+            Err(MeshError::UnknownCellGroup(_))
+        ));
+    }
+}
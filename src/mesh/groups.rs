@@ -0,0 +1,256 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::mesh::Mesh;
+use crate::mesh_errors::MeshError;
+
+type Groups = HashMap<Box<str>, Vec<usize>>;
+
+fn get_group<'a>(
+    groups: &'a Groups,
+    name: &str,
+    unknown: fn(String) -> MeshError,
+) -> Result<&'a [usize], MeshError> {
+    match groups.get(name) {
+        Some(ids) => Ok(ids),
+        None => Err(unknown(name.to_string())),
+    }
+}
+
+fn rename_group(
+    groups: &mut Groups,
+    name: &str,
+    new_name: &str,
+    unknown: fn(String) -> MeshError,
+    duplicate: fn(String) -> MeshError,
+) -> Result<(), MeshError> {
+    if !groups.contains_key(name) {
+        return Err(unknown(name.to_string()));
+    }
+    if name == new_name {
+        return Ok(());
+    }
+    if groups.contains_key(new_name) {
+        return Err(duplicate(new_name.to_string()));
+    }
+    if let Some(ids) = groups.remove(name) {
+        groups.insert(new_name.into(), ids);
+    }
+    Ok(())
+}
+
+fn append_to_group(
+    groups: &mut Groups,
+    name: &str,
+    ids: &[usize],
+    unknown: fn(String) -> MeshError,
+) -> Result<(), MeshError> {
+    let group = groups
+        .get_mut(name)
+        .ok_or_else(|| unknown(name.to_string()))?;
+    group.extend(ids);
+    group.sort();
+    group.dedup();
+    Ok(())
+}
+
+fn remove_from_group(
+    groups: &mut Groups,
+    name: &str,
+    ids: &[usize],
+    unknown: fn(String) -> MeshError,
+) -> Result<(), MeshError> {
+    let group = groups
+        .get_mut(name)
+        .ok_or_else(|| unknown(name.to_string()))?;
+    let ids: HashSet<&usize> = ids.iter().collect();
+    group.retain(|id| !ids.contains(id));
+    Ok(())
+}
+
+fn remove_from_all_groups(groups: &mut Groups, ids: &[usize]) {
+    let ids: HashSet<&usize> = ids.iter().collect();
+    for group in groups.values_mut() {
+        group.retain(|id| !ids.contains(id));
+    }
+}
+
+impl Mesh {
+    pub fn get_node_group(&self, name: &str) -> Result<&[usize], MeshError> {
+        get_group(&self.gno, name, MeshError::UnknownNodeGroup)
+    }
+
+    pub fn get_cell_group(&self, name: &str) -> Result<&[usize], MeshError> {
+        get_group(&self.gma, name, MeshError::UnknownCellGroup)
+    }
+
+    pub fn rename_node_group(&mut self, name: &str, new_name: &str) -> Result<(), MeshError> {
+        rename_group(
+            &mut self.gno,
+            name,
+            new_name,
+            MeshError::UnknownNodeGroup,
+            MeshError::DuplicateNodeGroup,
+        )
+    }
+
+    pub fn rename_cell_group(&mut self, name: &str, new_name: &str) -> Result<(), MeshError> {
+        rename_group(
+            &mut self.gma,
+            name,
+            new_name,
+            MeshError::UnknownCellGroup,
+            MeshError::DuplicateCellGroup,
+        )
+    }
+
+    // returns the node ids of the deleted group
+    pub fn delete_node_group(&mut self, name: &str) -> Result<Vec<usize>, MeshError> {
+        self.gno
+            .remove(name)
+            .ok_or_else(|| MeshError::UnknownNodeGroup(name.to_string()))
+    }
+
+    // returns the cell ids of the deleted group
+    pub fn delete_cell_group(&mut self, name: &str) -> Result<Vec<usize>, MeshError> {
+        self.gma
+            .remove(name)
+            .ok_or_else(|| MeshError::UnknownCellGroup(name.to_string()))
+    }
+
+    pub fn append_to_node_group(&mut self, name: &str, node_ids: &[usize]) -> Result<(), MeshError> {
+        self.check_node_ids(node_ids)?;
+        append_to_group(&mut self.gno, name, node_ids, MeshError::UnknownNodeGroup)
+    }
+
+    pub fn append_to_cell_group(&mut self, name: &str, cell_ids: &[usize]) -> Result<(), MeshError> {
+        self.check_cell_ids(cell_ids)?;
+        append_to_group(&mut self.gma, name, cell_ids, MeshError::UnknownCellGroup)
+    }
+
+    // node ids which are not in the group are ignored
+    pub fn remove_from_node_group(&mut self, name: &str, node_ids: &[usize]) -> Result<(), MeshError> {
+        remove_from_group(&mut self.gno, name, node_ids, MeshError::UnknownNodeGroup)
+    }
+
+    // cell ids which are not in the group are ignored
+    pub fn remove_from_cell_group(&mut self, name: &str, cell_ids: &[usize]) -> Result<(), MeshError> {
+        remove_from_group(&mut self.gma, name, cell_ids, MeshError::UnknownCellGroup)
+    }
+
+    // removes node ids from every group of nodes, groups which become empty are kept
+    pub fn remove_nodes_from_groups(&mut self, node_ids: &[usize]) {
+        remove_from_all_groups(&mut self.gno, node_ids);
+    }
+
+    // removes cell ids from every group of cells, groups which become empty are kept
+    pub fn remove_cells_from_groups(&mut self, cell_ids: &[usize]) {
+        remove_from_all_groups(&mut self.gma, cell_ids);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::mesh::Mesh;
+    use crate::mesh_errors::MeshError;
+
+    fn get_mesh_with_groups() -> Mesh {
+        let content = r#"COOR_3D
+N1 0. 0. 0.
+N2 1. 0. 0.
+N3 1. 1. 0.
+N4 0. 1. 0.
+FINSF
+SEG2
+M1 N1 N2
+M2 N2 N3
+M3 N3 N4
+FINSF
+GROUP_NO BOTTOM N1 N2
+FINSF
+GROUP_MA EDGES M1 M2
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn groups_from_parser_should_be_stored_by_type() {
+        let mesh = get_mesh_with_groups();
+        assert_eq!(mesh.get_node_group("BOTTOM").unwrap(), &[0, 1]);
+        assert_eq!(mesh.get_cell_group("EDGES").unwrap(), &[0, 1]);
+        assert!(matches!(
+            mesh.get_node_group("EDGES"),
+            Err(MeshError::UnknownNodeGroup(name)) if name == "EDGES"
+        ));
+        assert!(matches!(
+            mesh.get_cell_group("BOTTOM"),
+            Err(MeshError::UnknownCellGroup(name)) if name == "BOTTOM"
+        ));
+    }
+
+    #[test]
+    fn rename_group_should_work() {
+        let mut mesh = get_mesh_with_groups();
+        mesh.rename_node_group("BOTTOM", "BAS").unwrap();
+        assert_eq!(mesh.get_node_group("BAS").unwrap(), &[0, 1]);
+        assert!(mesh.get_node_group("BOTTOM").is_err());
+
+        mesh.create_cell_group("TOP", &[2]).unwrap();
+        assert!(matches!(
+            mesh.rename_cell_group("EDGES", "TOP"),
+            Err(MeshError::DuplicateCellGroup(name)) if name == "TOP"
+        ));
+        assert!(matches!(
+            mesh.rename_cell_group("MISSING", "OTHER"),
+            Err(MeshError::UnknownCellGroup(_))
+        ));
+        assert_eq!(mesh.get_cell_group("EDGES").unwrap(), &[0, 1]);
+    }
+
+    #[test]
+    fn delete_group_should_work() {
+        let mut mesh = get_mesh_with_groups();
+        assert_eq!(mesh.delete_node_group("BOTTOM").unwrap(), vec![0, 1]);
+        assert!(mesh.gno.is_empty());
+        assert_eq!(mesh.delete_cell_group("EDGES").unwrap(), vec![0, 1]);
+        assert!(mesh.delete_cell_group("EDGES").is_err());
+    }
+
+    #[test]
+    fn append_to_group_should_keep_sorted_unique_ids() {
+        let mut mesh = get_mesh_with_groups();
+        mesh.append_to_node_group("BOTTOM", &[3, 0, 3]).unwrap();
+        assert_eq!(mesh.get_node_group("BOTTOM").unwrap(), &[0, 1, 3]);
+        assert!(matches!(
+            mesh.append_to_node_group("BOTTOM", &[10]),
+            Err(MeshError::UnknownNodeId(10))
+        ));
+        assert!(matches!(
+            mesh.append_to_cell_group("EDGES", &[2, 5]),
+            Err(MeshError::UnknownCellId(5))
+        ));
+        assert_eq!(mesh.get_cell_group("EDGES").unwrap(), &[0, 1]);
+    }
+
+    #[test]
+    fn remove_from_group_should_work() {
+        let mut mesh = get_mesh_with_groups();
+        mesh.remove_from_node_group("BOTTOM", &[1, 3]).unwrap();
+        assert_eq!(mesh.get_node_group("BOTTOM").unwrap(), &[0]);
+        mesh.remove_from_cell_group("EDGES", &[0, 1]).unwrap();
+        assert!(mesh.get_cell_group("EDGES").unwrap().is_empty());
+    }
+
+    #[test]
+    fn remove_entities_from_groups_should_work() {
+        let mut mesh = get_mesh_with_groups();
+        mesh.create_node_group("ALL", &[0, 1, 2, 3]).unwrap();
+        mesh.remove_nodes_from_groups(&[1, 2]);
+        assert_eq!(mesh.get_node_group("BOTTOM").unwrap(), &[0]);
+        assert_eq!(mesh.get_node_group("ALL").unwrap(), &[0, 3]);
+        mesh.remove_cells_from_groups(&[1]);
+        assert_eq!(mesh.get_cell_group("EDGES").unwrap(), &[0]);
+    }
+}
@@ -0,0 +1,462 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::mesh::Mesh;
+use crate::mesh_errors::MeshError;
+
+// longest node and cell names accepted by Code_aster, and longest group names
+const MAIL_NAME_LENGTH: usize = 8;
+const MAIL_GROUP_NAME_LENGTH: usize = 24;
+
+// node and cell names of the .mail format: letters followed by digits
+pub(crate) fn is_valid_mail_name(name: &str) -> bool {
+    let digits = name.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    name.len() <= MAIL_NAME_LENGTH
+        && digits.len() < name.len()
+        && !digits.is_empty()
+        && digits.chars().all(|c| c.is_ascii_digit())
+}
+
+// group names of the .mail format: a letter followed by letters, digits or underscores
+pub(crate) fn is_valid_mail_group_name(name: &str) -> bool {
+    name.len() <= MAIL_GROUP_NAME_LENGTH
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+// checks that a node name can be written and read back
+pub(crate) fn check_node_name(name: &str) -> Result<(), MeshError> {
+    match is_valid_mail_name(name) {
+        true => Ok(()),
+        false => Err(MeshError::InvalidNodeName(name.to_string())),
+    }
+}
+
+// checks that a cell name can be written and read back
+pub(crate) fn check_cell_name(name: &str) -> Result<(), MeshError> {
+    match is_valid_mail_name(name) {
+        true => Ok(()),
+        false => Err(MeshError::InvalidCellName(name.to_string())),
+    }
+}
+
+// checks that a group name can be written and read back
+pub(crate) fn check_group_name(name: &str) -> Result<(), MeshError> {
+    match is_valid_mail_group_name(name) {
+        true => Ok(()),
+        false => Err(MeshError::InvalidGroupName(name.to_string())),
+    }
+}
+
+fn is_valid_prefix(prefix: &str) -> bool {
+    !prefix.is_empty()
+        && prefix.len() < MAIL_NAME_LENGTH
+        && prefix.chars().all(|c| c.is_ascii_alphabetic())
+}
+
+// Automatic naming of the nodes and cells created without a name.
+// Names are built from a prefix and the id + 1, e.g. `N12` for node id 11.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NamingScheme {
+    node_prefix: String,
+    cell_prefix: String,
+}
+
+impl NamingScheme {
+    // prefixes must be made of letters so that the generated names are valid
+    pub fn new(node_prefix: &str, cell_prefix: &str) -> Result<Self, MeshError> {
+        for prefix in [node_prefix, cell_prefix] {
+            if !is_valid_prefix(prefix) {
+                return Err(MeshError::InvalidNamePrefix(prefix.to_string()));
+            }
+        }
+        Ok(NamingScheme {
+            node_prefix: node_prefix.to_string(),
+            cell_prefix: cell_prefix.to_string(),
+        })
+    }
+
+    pub fn node_prefix(&self) -> &str {
+        &self.node_prefix
+    }
+
+    pub fn cell_prefix(&self) -> &str {
+        &self.cell_prefix
+    }
+
+    pub fn node_name(&self, node_id: usize) -> String {
+        format!("{}{}", self.node_prefix, node_id + 1)
+    }
+
+    pub fn cell_name(&self, cell_id: usize) -> String {
+        format!("{}{}", self.cell_prefix, cell_id + 1)
+    }
+}
+
+impl Default for NamingScheme {
+    fn default() -> Self {
+        NamingScheme {
+            node_prefix: "N".to_string(),
+            cell_prefix: "M".to_string(),
+        }
+    }
+}
+
+// Returns `prefix{id + 1}`, or the first following number giving a name which is not taken
+fn unique_name(prefix: &str, id: usize, is_taken: impl Fn(&str) -> bool) -> String {
+    let mut number = id + 1;
+    loop {
+        let name = format!("{}{}", prefix, number);
+        if !is_taken(&name) {
+            return name;
+        }
+        number += 1;
+    }
+}
+
+// Name generated for an id without stored name, when it does not depend on the other
+// ids: `prefix{id + 1}` if no entity is stored with that name
+fn primary_name(prefix: &str, id: usize, name_to_id: &HashMap<Box<str>, usize>) -> Option<String> {
+    let name = format!("{}{}", prefix, id + 1);
+    match name_to_id.contains_key(name.as_str()) {
+        true => None,
+        false => Some(name),
+    }
+}
+
+// Names of every id. Ids without stored name get `prefix{id + 1}`, or if that name is
+// stored for another entity the first following number whose name is neither stored,
+// generated for a lower id, nor `prefix{other_id + 1}` of another unnamed id
+fn all_names(
+    ids: Vec<usize>,
+    id_to_name: &HashMap<usize, Box<str>>,
+    name_to_id: &HashMap<Box<str>, usize>,
+    prefix: &str,
+) -> HashMap<usize, String> {
+    let mut ids = ids;
+    ids.sort();
+    let unnamed: HashSet<usize> = ids
+        .iter()
+        .filter(|id| !id_to_name.contains_key(id))
+        .copied()
+        .collect();
+    let mut generated: HashSet<String> = HashSet::new();
+    let mut names = HashMap::new();
+    for id in ids {
+        let name = match (id_to_name.get(&id), primary_name(prefix, id, name_to_id)) {
+            (Some(name), _) => name.to_string(),
+            (None, Some(name)) => name,
+            (None, None) => {
+                let mut number = id + 2;
+                loop {
+                    let name = format!("{}{}", prefix, number);
+                    if !name_to_id.contains_key(name.as_str())
+                        && !generated.contains(&name)
+                        && !unnamed.contains(&(number - 1))
+                    {
+                        break name;
+                    }
+                    number += 1;
+                }
+            }
+        };
+        generated.insert(name.clone());
+        names.insert(id, name);
+    }
+    names
+}
+
+impl Mesh {
+    pub fn node_id_by_name(&self, name: &str) -> Result<usize, MeshError> {
+        self.nodes_name_to_id
+            .get(name)
+            .copied()
+            .ok_or_else(|| MeshError::UnknownNodeName {
+                name: name.to_string(),
+                referenced_by: None,
+            })
+    }
+
+    pub fn cell_id_by_name(&self, name: &str) -> Result<usize, MeshError> {
+        self.cells_name_to_id
+            .get(name)
+            .copied()
+            .ok_or_else(|| MeshError::UnknownCellName {
+                name: name.to_string(),
+                referenced_by: None,
+            })
+    }
+
+    // stored name of the node, or the unique name generated by `node_names` if it has none
+    pub fn node_name(&self, node_id: usize) -> Result<String, MeshError> {
+        if !self.nodes.contains_key(&node_id) {
+            return Err(MeshError::UnknownNodeId(node_id));
+        }
+        match self.nodes_id_to_name.get(&node_id) {
+            Some(name) => Ok(name.to_string()),
+            None => match primary_name(&self.naming.node_prefix, node_id, &self.nodes_name_to_id) {
+                Some(name) => Ok(name),
+                None => Ok(self.node_names().remove(&node_id).unwrap_or_default()),
+            },
+        }
+    }
+
+    // stored name of the cell, or the unique name generated by `cell_names` if it has none
+    pub fn cell_name(&self, cell_id: usize) -> Result<String, MeshError> {
+        if !self.cells.contains_key(&cell_id) {
+            return Err(MeshError::UnknownCellId(cell_id));
+        }
+        match self.cells_id_to_name.get(&cell_id) {
+            Some(name) => Ok(name.to_string()),
+            None => match primary_name(&self.naming.cell_prefix, cell_id, &self.cells_name_to_id) {
+                Some(name) => Ok(name),
+                None => Ok(self.cell_names().remove(&cell_id).unwrap_or_default()),
+            },
+        }
+    }
+
+    // names of every node, nodes without stored name get a unique generated name
+    pub fn node_names(&self) -> HashMap<usize, String> {
+        all_names(
+            self.nodes.keys().copied().collect(),
+            &self.nodes_id_to_name,
+            &self.nodes_name_to_id,
+            &self.naming.node_prefix,
+        )
+    }
+
+    // names of every cell, cells without stored name get a unique generated name
+    pub fn cell_names(&self) -> HashMap<usize, String> {
+        all_names(
+            self.cells.keys().copied().collect(),
+            &self.cells_id_to_name,
+            &self.cells_name_to_id,
+            &self.naming.cell_prefix,
+        )
+    }
+
+    pub fn rename_node(&mut self, node_id: usize, name: &str) -> Result<(), MeshError> {
+        if !self.nodes.contains_key(&node_id) {
+            return Err(MeshError::UnknownNodeId(node_id));
+        }
+        check_node_name(name)?;
+        match self.nodes_name_to_id.get(name) {
+            Some(id) if *id == node_id => return Ok(()),
+            Some(_) => return Err(MeshError::DuplicateNodeName(name.to_string())),
+            None => (),
+        }
+        if let Some(old_name) = self.nodes_id_to_name.remove(&node_id) {
+            self.nodes_name_to_id.remove(&old_name);
+        }
+        self.nodes_name_to_id.insert(name.into(), node_id);
+        self.nodes_id_to_name.insert(node_id, name.into());
+        Ok(())
+    }
+
+    pub fn rename_cell(&mut self, cell_id: usize, name: &str) -> Result<(), MeshError> {
+        if !self.cells.contains_key(&cell_id) {
+            return Err(MeshError::UnknownCellId(cell_id));
+        }
+        check_cell_name(name)?;
+        match self.cells_name_to_id.get(name) {
+            Some(id) if *id == cell_id => return Ok(()),
+            Some(_) => return Err(MeshError::DuplicateCellName(name.to_string())),
+            None => (),
+        }
+        if let Some(old_name) = self.cells_id_to_name.remove(&cell_id) {
+            self.cells_name_to_id.remove(&old_name);
+        }
+        self.cells_name_to_id.insert(name.into(), cell_id);
+        self.cells_id_to_name.insert(cell_id, name.into());
+        Ok(())
+    }
+
+    // gives the node a name generated by the naming scheme
+    pub(crate) fn name_node_automatically(&mut self, node_id: usize) {
+        let name = unique_name(&self.naming.node_prefix, node_id, |name| {
+            self.nodes_name_to_id.contains_key(name)
+        });
+        self.nodes_name_to_id.insert(name.clone().into(), node_id);
+        self.nodes_id_to_name.insert(node_id, name.into());
+    }
+
+    // gives the cell a name generated by the naming scheme
+    pub(crate) fn name_cell_automatically(&mut self, cell_id: usize) {
+        let name = unique_name(&self.naming.cell_prefix, cell_id, |name| {
+            self.cells_name_to_id.contains_key(name)
+        });
+        self.cells_name_to_id.insert(name.clone().into(), cell_id);
+        self.cells_id_to_name.insert(cell_id, name.into());
+    }
+
+    // forgets the names of removed nodes
+    pub(crate) fn forget_node_names(&mut self, node_ids: &[usize]) {
+        for node_id in node_ids {
+            self.nodes_id_to_name.remove(node_id);
+        }
+        let removed: HashSet<&usize> = node_ids.iter().collect();
+        self.nodes_name_to_id
+            .retain(|_, node_id| !removed.contains(node_id));
+    }
+
+    // forgets the names of removed cells
+    pub(crate) fn forget_cell_names(&mut self, cell_ids: &[usize]) {
+        for cell_id in cell_ids {
+            if let Some(name) = self.cells_id_to_name.remove(cell_id) {
+                self.cells_name_to_id.remove(&name);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::{CellType, Node};
+    use crate::mesh::names::NamingScheme;
+    use crate::mesh::Mesh;
+    use crate::mesh_errors::MeshError;
+
+    fn get_named_mesh() -> Mesh {
+        let content = r#"COOR_3D
+N12 0. 0. 0.
+N3 1. 0. 0.
+FINSF
+SEG2
+M5 N12 N3
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn lookups_should_work_both_ways() {
+        let mesh = get_named_mesh();
+        assert_eq!(mesh.node_id_by_name("N12").unwrap(), 0);
+        assert_eq!(mesh.node_name(1).unwrap(), "N3");
+        assert_eq!(mesh.cell_id_by_name("M5").unwrap(), 0);
+        assert_eq!(mesh.cell_name(0).unwrap(), "M5");
+        assert!(matches!(
+            mesh.node_id_by_name("N1"),
+            Err(MeshError::UnknownNodeName { name, referenced_by: None }) if name == "N1"
+        ));
+        assert!(matches!(mesh.cell_name(3), Err(MeshError::UnknownCellId(3))));
+    }
+
+    #[test]
+    fn created_entities_should_be_named_automatically() {
+        let mut mesh = get_named_mesh();
+        let node_id = mesh.add_a_node(Node { x: 0., y: 1., z: 0. }, None).unwrap();
+        assert_eq!(node_id, 2);
+        assert_eq!(mesh.node_name(node_id).unwrap(), "N4");
+        // N3 is already used by node 1
+        let node_id = mesh.add_a_node(Node { x: 1., y: 1., z: 0. }, None).unwrap();
+        assert_eq!(mesh.node_name(node_id).unwrap(), "N5");
+        let cell_id = mesh.add_a_cell(CellType::SEG2, &[2, 3]).unwrap();
+        assert_eq!(mesh.cell_name(cell_id).unwrap(), "M2");
+        let cell_id = mesh.add_a_named_cell(CellType::SEG2, &[1, 3], "EDGE1").unwrap();
+        assert_eq!(mesh.cell_id_by_name("EDGE1").unwrap(), cell_id);
+        assert!(matches!(
+            mesh.add_a_named_cell(CellType::SEG2, &[1, 3], "M5"),
+            Err(MeshError::DuplicateCellName(name)) if name == "M5"
+        ));
+        assert_eq!(mesh.cells.len(), 3);
+    }
+
+    #[test]
+    fn naming_scheme_should_be_configurable() {
+        let mut mesh = Mesh::new();
+        mesh.naming = NamingScheme::new("NO", "MA").unwrap();
+        mesh.add_a_node(Node { x: 0., y: 0., z: 0. }, None).unwrap();
+        mesh.add_a_node(Node { x: 1., y: 0., z: 0. }, Some("TIP1")).unwrap();
+        mesh.add_a_cell(CellType::SEG2, &[0, 1]).unwrap();
+        assert_eq!(mesh.node_name(0).unwrap(), "NO1");
+        assert_eq!(mesh.node_name(1).unwrap(), "TIP1");
+        assert_eq!(mesh.cell_name(0).unwrap(), "MA1");
+        for prefix in ["", "N_", "N1", "NOEUDSXY"] {
+            assert!(matches!(
+                NamingScheme::new(prefix, "M"),
+                Err(MeshError::InvalidNamePrefix(name)) if name == prefix
+            ));
+        }
+    }
+
+    #[test]
+    fn names_which_cannot_be_read_back_should_be_refused() {
+        let mut mesh = get_named_mesh();
+        for name in ["TIP", "N_1", "1N", "N1A", "NOEUD1234"] {
+            assert!(matches!(
+                mesh.add_a_node(Node { x: 0., y: 1., z: 0. }, Some(name)),
+                Err(MeshError::InvalidNodeName(invalid)) if invalid == name
+            ));
+            assert!(matches!(
+                mesh.rename_node(0, name),
+                Err(MeshError::InvalidNodeName(_))
+            ));
+            assert!(matches!(
+                mesh.add_a_named_cell(CellType::SEG2, &[0, 1], name),
+                Err(MeshError::InvalidCellName(_))
+            ));
+            assert!(matches!(mesh.rename_cell(0, name), Err(MeshError::InvalidCellName(_))));
+        }
+        assert_eq!(mesh.nodes.len(), 2);
+        assert_eq!(mesh.cells.len(), 1);
+        assert_eq!(mesh.node_name(0).unwrap(), "N12");
+        assert!(matches!(
+            Mesh::create_from_parser_output(
+                mail_parser("COOR_3D\nNOEUD12345 0. 0. 0.\nFINSF\nFIN").unwrap()
+            ),
+            Err(MeshError::InvalidNodeName(_))
+        ));
+    }
+
+    #[test]
+    fn rename_should_update_both_mappings() {
+        let mut mesh = get_named_mesh();
+        mesh.rename_node(0, "N1").unwrap();
+        assert_eq!(mesh.node_name(0).unwrap(), "N1");
+        assert!(mesh.node_id_by_name("N12").is_err());
+        assert!(matches!(
+            mesh.rename_node(0, "N3"),
+            Err(MeshError::DuplicateNodeName(name)) if name == "N3"
+        ));
+        assert!(mesh.rename_node(0, "N1").is_ok());
+        mesh.rename_cell(0, "BEAM1").unwrap();
+        assert_eq!(mesh.cell_id_by_name("BEAM1").unwrap(), 0);
+        assert!(matches!(mesh.rename_cell(1, "M1"), Err(MeshError::UnknownCellId(1))));
+    }
+
+    #[test]
+    fn names_should_be_generated_for_unnamed_entities() {
+        let mut mesh = get_named_mesh();
+        // nodes inserted directly have no stored name
+        mesh.nodes.insert(2, Node { x: 0., y: 1., z: 0. });
+        mesh.nodes.insert(11, Node { x: 1., y: 1., z: 0. });
+        let names = mesh.node_names();
+        assert_eq!(names[&0], "N12");
+        assert_eq!(names[&1], "N3");
+        assert_eq!(names[&2], "N4");
+        // N12 is already used by node 0
+        assert_eq!(names[&11], "N13");
+        assert_eq!(mesh.node_name(11).unwrap(), "N13");
+        assert_eq!(mesh.node_name(2).unwrap(), "N4");
+    }
+
+    #[test]
+    fn generated_names_should_not_shadow_stored_names() {
+        let mut mesh = Mesh::new();
+        mesh.add_a_node(Node { x: 0., y: 0., z: 0. }, Some("N2")).unwrap();
+        mesh.nodes.insert(1, Node { x: 1., y: 0., z: 0. });
+        assert_eq!(mesh.node_name(1).unwrap(), "N3");
+        assert_eq!(mesh.node_names()[&1], "N3");
+        // N3 is kept for node 2
+        mesh.nodes.insert(2, Node { x: 2., y: 0., z: 0. });
+        assert_eq!(mesh.node_name(1).unwrap(), "N4");
+        assert_eq!(mesh.node_name(2).unwrap(), "N3");
+        assert_eq!(mesh.node_names()[&1], "N4");
+        mesh.add_a_named_cell(CellType::SEG2, &[0, 1], "M2").unwrap();
+        mesh.cells.insert(1, mesh.cells[&0].clone());
+        assert_eq!(mesh.cell_name(1).unwrap(), mesh.cell_names()[&1]);
+        assert_eq!(mesh.cell_name(1).unwrap(), "M3");
+    }
+}
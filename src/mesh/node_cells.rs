@@ -0,0 +1,134 @@
+use crate::mesh::Mesh;
+
+// Node to cells incidence, stored in compressed rows: the cells of node `n` are
+// `cell_ids[offsets[n]..offsets[n + 1]]`, sorted.
+// It is a snapshot of the mesh and must be built again after the cells are modified
+#[derive(Debug, Clone, PartialEq)]
+pub struct NodeCells {
+    offsets: Vec<usize>,
+    cell_ids: Vec<usize>,
+}
+
+impl NodeCells {
+    // ids of the cells using the node, empty for unknown nodes
+    pub fn cells(&self, node_id: usize) -> &[usize] {
+        if node_id + 1 >= self.offsets.len() {
+            return &[];
+        }
+        &self.cell_ids[self.offsets[node_id]..self.offsets[node_id + 1]]
+    }
+
+    pub fn nb_of_cells(&self, node_id: usize) -> usize {
+        self.cells(node_id).len()
+    }
+}
+
+impl Mesh {
+    // builds the node to cells incidence, a cell using a node twice is listed once
+    pub fn node_cells(&self) -> NodeCells {
+        let nb_rows = self
+            .nodes
+            .keys()
+            .chain(self.cells.values().flat_map(|cell| cell.co.iter()))
+            .max()
+            .map_or(0, |node_id| node_id + 1);
+        let mut cell_ids: Vec<usize> = self.cells.keys().copied().collect();
+        cell_ids.sort();
+
+        // unique nodes of every cell
+        let cell_nodes: Vec<(usize, Vec<usize>)> = cell_ids
+            .into_iter()
+            .map(|cell_id| {
+                let mut co = self.cells[&cell_id].co.clone();
+                co.sort();
+                co.dedup();
+                (cell_id, co)
+            })
+            .collect();
+
+        let mut offsets = vec![0; nb_rows + 1];
+        for (_, co) in cell_nodes.iter() {
+            for node_id in co {
+                offsets[node_id + 1] += 1;
+            }
+        }
+        for row in 0..nb_rows {
+            offsets[row + 1] += offsets[row];
+        }
+        // cells are visited by increasing id so every row is sorted
+        let mut next = offsets.clone();
+        let mut incidence = vec![0; offsets[nb_rows]];
+        for (cell_id, co) in cell_nodes {
+            for node_id in co {
+                incidence[next[node_id]] = cell_id;
+                next[node_id] += 1;
+            }
+        }
+        NodeCells {
+            offsets,
+            cell_ids: incidence,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::CellType;
+    use crate::mesh::Mesh;
+
+    fn get_mesh() -> Mesh {
+        let content = r#"COOR_2D
+N1 0. 0.
+N2 1. 0.
+N3 1. 1.
+N4 0. 1.
+N5 2. 0.
+FINSF
+TRIA3
+M1 N1 N2 N3
+M2 N1 N3 N4
+M3 N2 N5 N3
+FINSF
+POI1
+M4 N3
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn node_cells_should_list_sorted_incident_cells() {
+        let mesh = get_mesh();
+        let node_cells = mesh.node_cells();
+        assert_eq!(node_cells.cells(0), &[0, 1]);
+        assert_eq!(node_cells.cells(2), &[0, 1, 2, 3]);
+        assert_eq!(node_cells.cells(4), &[2]);
+        assert_eq!(node_cells.nb_of_cells(3), 1);
+        assert!(node_cells.cells(12).is_empty());
+    }
+
+    #[test]
+    fn node_cells_should_follow_mesh_modifications() {
+        let mut mesh = get_mesh();
+        mesh.remove_cells(&[0]).unwrap();
+        mesh.edit_cell(2, &[1, 4, 4], CellType::TRIA3).unwrap();
+        let node_id = mesh
+            .add_a_node(
+                crate::lib::Node {
+                    x: 3.,
+                    y: 0.,
+                    z: 0.,
+                },
+                None,
+            )
+            .unwrap();
+        let node_cells = mesh.node_cells();
+        assert_eq!(node_cells.cells(1), &[2]);
+        // a degenerate cell is listed once
+        assert_eq!(node_cells.cells(4), &[2]);
+        assert_eq!(node_cells.cells(2), &[1, 3]);
+        assert!(node_cells.cells(node_id).is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::mesh::{IdMapping, Mesh};
+use crate::mesh_enums::RenumberingAlgorithm;
+
+// Sloan weights for the distance to the end node and for the current degree
+const SLOAN_W1: i64 = 1;
+const SLOAN_W2: i64 = 2;
+
+// bandwidth and profile of the node adjacency graph, before and after a renumbering
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenumberingReport {
+    pub bandwidth_before: usize,
+    pub bandwidth_after: usize,
+    pub profile_before: usize,
+    pub profile_after: usize,
+    pub mapping: IdMapping,
+}
+
+// Node adjacency graph: nodes sharing a cell are neighbours.
+// Vertices are the positions of the nodes in the sorted list of node ids
+struct NodeGraph {
+    node_ids: Vec<usize>,
+    neighbours: Vec<Vec<usize>>,
+}
+
+impl NodeGraph {
+    fn new(mesh: &Mesh) -> Self {
+        let mut node_ids: Vec<usize> = mesh.nodes.keys().copied().collect();
+        node_ids.sort();
+        let positions: HashMap<usize, usize> = node_ids
+            .iter()
+            .enumerate()
+            .map(|(position, node_id)| (*node_id, position))
+            .collect();
+        let mut neighbours = vec![vec![]; node_ids.len()];
+        for cell in mesh.cells.values() {
+            let vertices: Vec<usize> = cell
+                .co
+                .iter()
+                .filter_map(|node_id| positions.get(node_id).copied())
+                .collect();
+            for vertex in vertices.iter() {
+                neighbours[*vertex].extend(vertices.iter().filter(|other| *other != vertex));
+            }
+        }
+        for vertex_neighbours in neighbours.iter_mut() {
+            vertex_neighbours.sort();
+            vertex_neighbours.dedup();
+        }
+        NodeGraph {
+            node_ids,
+            neighbours,
+        }
+    }
+
+    fn degree(&self, vertex: usize) -> usize {
+        self.neighbours[vertex].len()
+    }
+
+    // vertices grouped by distance to the root, restricted to the root component
+    fn level_structure(&self, root: usize) -> Vec<Vec<usize>> {
+        let mut visited = vec![false; self.neighbours.len()];
+        visited[root] = true;
+        let mut levels = vec![vec![root]];
+        loop {
+            let mut next_level = vec![];
+            for vertex in levels.last().unwrap() {
+                for neighbour in self.neighbours[*vertex].iter() {
+                    if !visited[*neighbour] {
+                        visited[*neighbour] = true;
+                        next_level.push(*neighbour);
+                    }
+                }
+            }
+            if next_level.is_empty() {
+                return levels;
+            }
+            levels.push(next_level);
+        }
+    }
+
+    // George and Liu algorithm, returns a start and an end vertex far from each other
+    fn pseudo_peripheral_pair(&self, vertex: usize) -> (usize, usize) {
+        let mut start = vertex;
+        let mut levels = self.level_structure(start);
+        loop {
+            let end = *levels
+                .last()
+                .unwrap()
+                .iter()
+                .min_by_key(|vertex| self.degree(**vertex))
+                .unwrap();
+            let end_levels = self.level_structure(end);
+            if end_levels.len() <= levels.len() {
+                return (start, end);
+            }
+            start = end;
+            levels = end_levels;
+        }
+    }
+
+    // one vertex of minimal degree per connected component
+    fn component_roots(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.neighbours.len()];
+        let mut roots = vec![];
+        for vertex in 0..self.neighbours.len() {
+            if visited[vertex] {
+                continue;
+            }
+            let component: Vec<usize> = self.level_structure(vertex).concat();
+            for other in component.iter() {
+                visited[*other] = true;
+            }
+            roots.push(*component.iter().min_by_key(|v| self.degree(**v)).unwrap());
+        }
+        roots
+    }
+
+    fn reverse_cuthill_mckee(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.neighbours.len()];
+        let mut order = vec![];
+        for root in self.component_roots() {
+            let (start, _) = self.pseudo_peripheral_pair(root);
+            let mut component_order = vec![];
+            let mut queue = VecDeque::from([start]);
+            visited[start] = true;
+            while let Some(vertex) = queue.pop_front() {
+                component_order.push(vertex);
+                let mut next: Vec<usize> = self.neighbours[vertex]
+                    .iter()
+                    .filter(|neighbour| !visited[**neighbour])
+                    .copied()
+                    .collect();
+                next.sort_by_key(|neighbour| self.degree(*neighbour));
+                for neighbour in next {
+                    visited[neighbour] = true;
+                    queue.push_back(neighbour);
+                }
+            }
+            component_order.reverse();
+            order.extend(component_order);
+        }
+        order
+    }
+
+    fn sloan(&self) -> Vec<usize> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Status {
+            Inactive,
+            Preactive,
+            Active,
+            Postactive,
+        }
+        let nb_vertices = self.neighbours.len();
+        let mut status = vec![Status::Inactive; nb_vertices];
+        let mut priority = vec![0; nb_vertices];
+        let mut order = vec![];
+        for root in self.component_roots() {
+            let (start, end) = self.pseudo_peripheral_pair(root);
+            for (distance, level) in self.level_structure(end).iter().enumerate() {
+                for vertex in level {
+                    priority[*vertex] =
+                        SLOAN_W1 * distance as i64 - SLOAN_W2 * (self.degree(*vertex) as i64 + 1);
+                }
+            }
+            let mut queue = vec![start];
+            status[start] = Status::Preactive;
+            while !queue.is_empty() {
+                // vertex of highest priority, the first one queued on ties
+                let mut index = 0;
+                for (i, vertex) in queue.iter().enumerate() {
+                    if priority[*vertex] > priority[queue[index]] {
+                        index = i;
+                    }
+                }
+                let vertex = queue.remove(index);
+                if status[vertex] == Status::Preactive {
+                    for neighbour in self.neighbours[vertex].iter() {
+                        priority[*neighbour] += SLOAN_W2;
+                        if status[*neighbour] == Status::Inactive {
+                            status[*neighbour] = Status::Preactive;
+                            queue.push(*neighbour);
+                        }
+                    }
+                }
+                order.push(vertex);
+                status[vertex] = Status::Postactive;
+                for neighbour in self.neighbours[vertex].iter() {
+                    if status[*neighbour] != Status::Preactive {
+                        continue;
+                    }
+                    status[*neighbour] = Status::Active;
+                    priority[*neighbour] += SLOAN_W2;
+                    for next in self.neighbours[*neighbour].iter() {
+                        if status[*next] == Status::Postactive {
+                            continue;
+                        }
+                        priority[*next] += SLOAN_W2;
+                        if status[*next] == Status::Inactive {
+                            status[*next] = Status::Preactive;
+                            queue.push(*next);
+                        }
+                    }
+                }
+            }
+        }
+        order
+    }
+
+    // bandwidth and profile when vertices are numbered by `ranks`
+    fn bandwidth_and_profile(&self, ranks: &[usize]) -> (usize, usize) {
+        let mut bandwidth = 0;
+        let mut profile = 0;
+        for (vertex, vertex_neighbours) in self.neighbours.iter().enumerate() {
+            let rank = ranks[vertex];
+            let mut first = rank;
+            for neighbour in vertex_neighbours {
+                let neighbour_rank = ranks[*neighbour];
+                bandwidth = bandwidth.max(rank.abs_diff(neighbour_rank));
+                first = first.min(neighbour_rank);
+            }
+            profile += rank - first;
+        }
+        (bandwidth, profile)
+    }
+}
+
+impl Mesh {
+    // greatest difference between the ids of two nodes sharing a cell,
+    // ids being counted as positions in the sorted list of node ids
+    pub fn node_bandwidth(&self) -> usize {
+        let graph = NodeGraph::new(self);
+        let ranks: Vec<usize> = (0..graph.node_ids.len()).collect();
+        graph.bandwidth_and_profile(&ranks).0
+    }
+
+    // sum over nodes of the distance to their first neighbour, i.e. the size of
+    // the skyline of a matrix assembled on the nodes (diagonal excluded)
+    pub fn node_profile(&self) -> usize {
+        let graph = NodeGraph::new(self);
+        let ranks: Vec<usize> = (0..graph.node_ids.len()).collect();
+        graph.bandwidth_and_profile(&ranks).1
+    }
+
+    // renumbers nodes 0..n to reduce the bandwidth or the profile of the node
+    // adjacency graph. Cells, groups and names are updated, cell ids are kept
+    pub fn renumber_nodes(&mut self, algorithm: RenumberingAlgorithm) -> RenumberingReport {
+        let graph = NodeGraph::new(self);
+        let initial_ranks: Vec<usize> = (0..graph.node_ids.len()).collect();
+        let (bandwidth_before, profile_before) = graph.bandwidth_and_profile(&initial_ranks);
+
+        let order = match algorithm {
+            RenumberingAlgorithm::ReverseCuthillMcKee => graph.reverse_cuthill_mckee(),
+            RenumberingAlgorithm::Sloan => graph.sloan(),
+        };
+        let mut ranks = vec![0; order.len()];
+        for (rank, vertex) in order.iter().enumerate() {
+            ranks[*vertex] = rank;
+        }
+        let (bandwidth_after, profile_after) = graph.bandwidth_and_profile(&ranks);
+
+        let mapping = IdMapping {
+            nodes: graph
+                .node_ids
+                .iter()
+                .zip(ranks.iter())
+                .map(|(node_id, rank)| (*node_id, *rank))
+                .collect(),
+            cells: HashMap::new(),
+        };
+        self.renumber(&mapping);
+        RenumberingReport {
+            bandwidth_before,
+            bandwidth_after,
+            profile_before,
+            profile_after,
+            mapping,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::{CellType, Node};
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::RenumberingAlgorithm;
+
+    // structured grid of QUAD4 cells whose nodes are created in a scattered order,
+    // (nx + 1) * (ny + 1) must not be a multiple of 7
+    fn get_grid_mesh(nx: usize, ny: usize) -> Mesh {
+        let mut mesh = Mesh::new();
+        let nb_nodes = (nx + 1) * (ny + 1);
+        let mut ids = vec![0; nb_nodes];
+        for k in 0..nb_nodes {
+            let position = (k * 7) % nb_nodes;
+            let (i, j) = (position / (ny + 1), position % (ny + 1));
+            let node = Node { x: i as f64, y: j as f64, z: 0. };
+            ids[position] = mesh.add_a_node(node, None).unwrap();
+        }
+        let id = |i: usize, j: usize| ids[i * (ny + 1) + j];
+        for i in 0..nx {
+            for j in 0..ny {
+                let co = [id(i, j), id(i + 1, j), id(i + 1, j + 1), id(i, j + 1)];
+                mesh.add_a_cell(CellType::QUAD4, &co).unwrap();
+            }
+        }
+        mesh
+    }
+
+    #[test]
+    fn bandwidth_and_profile_should_work() {
+        let mut mesh = Mesh::new();
+        for x in 0..4 {
+            mesh.add_a_node(Node { x: x as f64, y: 0., z: 0. }, None).unwrap();
+        }
+        mesh.add_a_cell(CellType::SEG2, &[0, 3]).unwrap();
+        mesh.add_a_cell(CellType::SEG2, &[3, 1]).unwrap();
+        mesh.add_a_cell(CellType::SEG2, &[1, 2]).unwrap();
+        assert_eq!(mesh.node_bandwidth(), 3);
+        // rows 1, 2 and 3 begin at columns 1, 1 and 0
+        assert_eq!(mesh.node_profile(), 4);
+    }
+
+    #[test]
+    fn reverse_cuthill_mckee_should_reduce_bandwidth() {
+        let mut mesh = get_grid_mesh(8, 3);
+        let report = mesh.renumber_nodes(RenumberingAlgorithm::ReverseCuthillMcKee);
+        // numbering the grid column by column gives a bandwidth of 7
+        assert!(report.bandwidth_after <= 8);
+        assert!(report.bandwidth_after < report.bandwidth_before);
+        assert!(report.profile_after < report.profile_before);
+        assert_eq!(report.bandwidth_after, mesh.node_bandwidth());
+        assert_eq!(report.profile_after, mesh.node_profile());
+    }
+
+    #[test]
+    fn sloan_should_reduce_profile() {
+        let mut mesh = get_grid_mesh(8, 3);
+        let report = mesh.renumber_nodes(RenumberingAlgorithm::Sloan);
+        assert!(report.profile_after < report.profile_before);
+        assert_eq!(report.profile_after, mesh.node_profile());
+    }
+
+    #[test]
+    fn renumbering_should_keep_the_mesh_consistent() {
+        let mut mesh = get_grid_mesh(4, 2);
+        // an isolated node and a hole in the numbering
+        let isolated = mesh.add_a_node(Node { x: 9., y: 9., z: 9. }, Some("ALONE1")).unwrap();
+        mesh.remove_cells(&[0]).unwrap();
+        mesh.create_node_group("ALL", &mesh.nodes.keys().copied().collect::<Vec<usize>>())
+            .unwrap();
+        let corner = mesh.cells[&1].co.clone();
+        let coords: Vec<Node> = corner.iter().map(|node_id| mesh.nodes[node_id].clone()).collect();
+        let nb_nodes = mesh.nodes.len();
+
+        let report = mesh.renumber_nodes(RenumberingAlgorithm::ReverseCuthillMcKee);
+
+        let mut node_ids: Vec<usize> = mesh.nodes.keys().copied().collect();
+        node_ids.sort();
+        assert_eq!(node_ids, (0..nb_nodes).collect::<Vec<usize>>());
+        assert_eq!(mesh.next_node_id, nb_nodes);
+        let renumbered: Vec<Node> = mesh.cells[&1]
+            .co
+            .iter()
+            .map(|node_id| mesh.nodes[node_id].clone())
+            .collect();
+        assert_eq!(renumbered, coords);
+        assert_eq!(mesh.get_node_group("ALL").unwrap(), node_ids.as_slice());
+        assert_eq!(mesh.node_id_by_name("ALONE1").unwrap(), report.mapping.nodes[&isolated]);
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::mesh::geometry::{cross, dot};
+use crate::mesh::Mesh;
+use crate::mesh_enums::OrientationReference;
+use crate::mesh_errors::MeshError;
+
+// Normal of a polygon given by its corners, whose norm is twice its area, and its center
+fn normal_and_center(corners: &[[f64; 3]]) -> ([f64; 3], [f64; 3]) {
+    let mut normal = [0.; 3];
+    let mut center = [0.; 3];
+    for (i, corner) in corners.iter().enumerate() {
+        let product = cross(*corner, corners[(i + 1) % corners.len()]);
+        for k in 0..3 {
+            normal[k] += product[k];
+            center[k] += corner[k] / corners.len() as f64;
+        }
+    }
+    (normal, center)
+}
+
+impl Mesh {
+    // reverses the orientation of a cell by permuting its nodes, see `reversed_node_order`
+    pub fn reverse_cell(&mut self, cell_id: usize) -> Result<(), MeshError> {
+        let cell = self
+            .cells
+            .get_mut(&cell_id)
+            .ok_or(MeshError::UnknownCellId(cell_id))?;
+        let co: Vec<usize> = cell
+            .ty
+            .reversed_node_order()
+            .iter()
+            .map(|i| cell.co[*i])
+            .collect();
+        cell.co = co;
+        Ok(())
+    }
+
+    // Orients the surface cells of a group consistently across the edges they share,
+    // other cells of the group are ignored. Each edge-connected part keeps the orientation
+    // of its lowest cell id unless a reference is given, in which case its normals are
+    // turned towards the reference on average. Returns the ids of the reversed cells
+    pub fn orient_shell_group(
+        &mut self,
+        name: &str,
+        reference: Option<&OrientationReference>,
+    ) -> Result<Vec<usize>, MeshError> {
+        let mut cell_ids: Vec<usize> = self
+            .get_cell_group(name)?
+            .iter()
+            .filter(|cell_id| self.cells[cell_id].ty.topological_dimension() == 2)
+            .copied()
+            .collect();
+        cell_ids.sort();
+        cell_ids.dedup();
+
+        // edges by sorted corner ids, with the cells going along them from the lowest id
+        let mut edges: HashMap<(usize, usize), Vec<(usize, bool)>> = HashMap::new();
+        let mut geometry = HashMap::new();
+        for cell_id in cell_ids.iter() {
+            let cell = &self.cells[cell_id];
+            let corners = &cell.co[cell.ty.corner_node_indices()];
+            for (i, a) in corners.iter().enumerate() {
+                let b = corners[(i + 1) % corners.len()];
+                edges
+                    .entry((*a.min(&b), *a.max(&b)))
+                    .or_default()
+                    .push((*cell_id, *a < b));
+            }
+            let coords = self.cell_coordinates(*cell_id)?;
+            geometry.insert(*cell_id, normal_and_center(&coords[..corners.len()]));
+        }
+
+        let mut reversed: HashMap<usize, bool> = HashMap::new();
+        for seed in cell_ids.iter() {
+            if reversed.contains_key(seed) {
+                continue;
+            }
+            // breadth first traversal of the part, the first orientation found is kept
+            let mut part = vec![*seed];
+            let mut queue = VecDeque::from([*seed]);
+            reversed.insert(*seed, false);
+            while let Some(cell_id) = queue.pop_front() {
+                let cell = &self.cells[&cell_id];
+                let corners = &cell.co[cell.ty.corner_node_indices()];
+                for (i, a) in corners.iter().enumerate() {
+                    let b = corners[(i + 1) % corners.len()];
+                    // neighbours must go along the edge in the opposite direction
+                    let forward = (*a < b) != reversed[&cell_id];
+                    for (neighbour, along) in edges[&(*a.min(&b), *a.max(&b))].iter() {
+                        if !reversed.contains_key(neighbour) {
+                            reversed.insert(*neighbour, *along == forward);
+                            part.push(*neighbour);
+                            queue.push_back(*neighbour);
+                        }
+                    }
+                }
+            }
+            if let Some(reference) = reference {
+                let alignment: f64 = part
+                    .iter()
+                    .map(|cell_id| {
+                        let (normal, center) = geometry[cell_id];
+                        let direction = match reference {
+                            OrientationReference::AwayFromPoint(point) => [
+                                center[0] - point.x,
+                                center[1] - point.y,
+                                center[2] - point.z,
+                            ],
+                            OrientationReference::Along(vector) => [vector.x, vector.y, vector.z],
+                        };
+                        let sign = if reversed[cell_id] { -1. } else { 1. };
+                        sign * dot(normal, direction)
+                    })
+                    .sum();
+                if alignment < 0. {
+                    for cell_id in part {
+                        reversed.insert(cell_id, !reversed[&cell_id]);
+                    }
+                }
+            }
+        }
+
+        let mut reversed: Vec<usize> = reversed
+            .into_iter()
+            .filter(|(_, reversed)| *reversed)
+            .map(|(cell_id, _)| cell_id)
+            .collect();
+        reversed.sort();
+        for cell_id in reversed.iter() {
+            self.reverse_cell(*cell_id)?;
+        }
+        Ok(reversed)
+    }
+
+    // Reverses the volume cells whose volume is negative, returns their ids
+    pub fn fix_inverted_cells(&mut self) -> Result<Vec<usize>, MeshError> {
+        let mut inverted = vec![];
+        for (cell_id, cell) in self.cells.iter() {
+            if cell.ty.topological_dimension() == 3 && self.cell_measure(*cell_id)? < 0. {
+                inverted.push(*cell_id);
+            }
+        }
+        inverted.sort();
+        for cell_id in inverted.iter() {
+            self.reverse_cell(*cell_id)?;
+        }
+        Ok(inverted)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::{CellType, Node};
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::OrientationReference;
+    use crate::mesh_errors::MeshError;
+    use crate::shape_functions::reference_coordinates;
+
+    fn add_nodes(mesh: &mut Mesh, points: &[[f64; 3]]) -> Vec<usize> {
+        points
+            .iter()
+            .map(|[x, y, z]| {
+                mesh.add_a_node(
+                    Node {
+                        x: *x,
+                        y: *y,
+                        z: *z,
+                    },
+                    None,
+                )
+                .unwrap()
+            })
+            .collect()
+    }
+
+    // 2 x 2 grid of nodes 0..9 on z = 0, with normals along z
+    fn get_plate() -> Mesh {
+        let mut mesh = Mesh::new();
+        let points: Vec<[f64; 3]> = (0..9)
+            .map(|i| [(i % 3) as f64, (i / 3) as f64, 0.])
+            .collect();
+        add_nodes(&mut mesh, &points);
+        mesh.add_a_cell(CellType::QUAD4, &[0, 1, 4, 3]).unwrap();
+        mesh.add_a_cell(CellType::QUAD4, &[1, 2, 5, 4]).unwrap();
+        mesh.add_a_cell(CellType::TRIA3, &[3, 4, 7]).unwrap();
+        mesh.add_a_cell(CellType::TRIA3, &[4, 8, 7]).unwrap();
+        mesh.add_a_cell(CellType::TRIA3, &[4, 5, 8]).unwrap();
+        mesh
+    }
+
+    #[test]
+    fn shells_should_be_oriented_consistently() {
+        let mut mesh = get_plate();
+        mesh.reverse_cell(1).unwrap();
+        mesh.reverse_cell(3).unwrap();
+        assert_eq!(mesh.cells[&1].co, vec![1, 4, 5, 2]);
+        mesh.create_cell_group("PLATE", &[0, 1, 2, 3, 4]).unwrap();
+        assert_eq!(mesh.orient_shell_group("PLATE", None).unwrap(), vec![1, 3]);
+        assert_eq!(mesh.cells[&1].co, vec![1, 2, 5, 4]);
+        assert_eq!(mesh.orient_shell_group("PLATE", None).unwrap(), vec![]);
+
+        let down = OrientationReference::Along(Node {
+            x: 0.,
+            y: 0.,
+            z: -1.,
+        });
+        assert_eq!(
+            mesh.orient_shell_group("PLATE", Some(&down)).unwrap(),
+            vec![0, 1, 2, 3, 4]
+        );
+        let below = OrientationReference::AwayFromPoint(Node {
+            x: 1.,
+            y: 1.,
+            z: -5.,
+        });
+        assert_eq!(
+            mesh.orient_shell_group("PLATE", Some(&below))
+                .unwrap()
+                .len(),
+            5
+        );
+        assert!(matches!(
+            mesh.orient_shell_group("MISSING", None),
+            Err(MeshError::UnknownCellGroup(_))
+        ));
+    }
+
+    #[test]
+    fn reversed_quadratic_shells_should_keep_their_mid_nodes() {
+        let mut mesh = Mesh::new();
+        let nodes = add_nodes(&mut mesh, &reference_coordinates(&CellType::TRIA6));
+        let cell_id = mesh.add_a_cell(CellType::TRIA6, &nodes).unwrap();
+        mesh.reverse_cell(cell_id).unwrap();
+        assert_eq!(mesh.cells[&cell_id].co, vec![0, 2, 1, 5, 4, 3]);
+    }
+
+    #[test]
+    fn inverted_volumes_should_be_fixed() {
+        let types = [
+            CellType::HEXA8,
+            CellType::HEXA20,
+            CellType::HEXA27,
+            CellType::PENTA6,
+            CellType::PENTA15,
+            CellType::PENTA18,
+            CellType::TETRA4,
+            CellType::TETRA10,
+            CellType::PYRAM5,
+            CellType::PYRAM13,
+        ];
+        let mut mesh = Mesh::new();
+        for cell_type in types.iter() {
+            // reference element mapped by x -> 2x, y -> 3y, z -> z
+            let points: Vec<[f64; 3]> = reference_coordinates(cell_type)
+                .into_iter()
+                .map(|[x, y, z]| [2. * x, 3. * y, z])
+                .collect();
+            let nodes = add_nodes(&mut mesh, &points);
+            let cell_id = mesh.add_a_cell(cell_type.clone(), &nodes).unwrap();
+            let volume = mesh.cell_measure(cell_id).unwrap();
+            mesh.reverse_cell(cell_id).unwrap();
+            let reversed = mesh.cell_measure(cell_id).unwrap();
+            assert!(
+                (reversed + volume).abs() < 1e-10 * volume,
+                "{}",
+                mesh.cells[&cell_id].ty
+            );
+        }
+        let inverted = mesh.fix_inverted_cells().unwrap();
+        assert_eq!(inverted, (0..types.len()).collect::<Vec<_>>());
+        assert_eq!(mesh.find_defective_cells().unwrap(), vec![]);
+        assert_eq!(mesh.fix_inverted_cells().unwrap(), vec![]);
+    }
+}
@@ -0,0 +1,505 @@
+use std::collections::HashMap;
+use std::f64::consts::FRAC_1_SQRT_2;
+
+use crate::mesh::geometry::{cross, dot, norm};
+use crate::mesh::Mesh;
+use crate::mesh_enums::{CellType, QualityMetric};
+use crate::mesh_errors::MeshError;
+use crate::shape_functions::reference_coordinates;
+
+// Quality measures of a cell, computed on its corner nodes. Measures which do not
+// apply to the cell type are None. Angles are in degrees
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CellQuality {
+    // longest edge over shortest edge, 1 for the ideal cell
+    pub aspect_ratio: Option<f64>,
+    // smallest jacobian determinant at the corners, normalised by the edge lengths,
+    // 1 for the ideal cell, negative for inverted cells
+    pub scaled_jacobian: Option<f64>,
+    // equiangle skewness of the faces, 0 for the ideal cell and 1 for a degenerate one
+    pub skewness: Option<f64>,
+    // angle between the two halves of a quadrangle split along a diagonal
+    pub warpage: Option<f64>,
+    pub min_dihedral_angle: Option<f64>,
+    pub max_dihedral_angle: Option<f64>,
+}
+
+impl CellQuality {
+    pub fn get(&self, metric: QualityMetric) -> Option<f64> {
+        match metric {
+            QualityMetric::AspectRatio => self.aspect_ratio,
+            QualityMetric::ScaledJacobian => self.scaled_jacobian,
+            QualityMetric::Skewness => self.skewness,
+            QualityMetric::Warpage => self.warpage,
+            QualityMetric::MinDihedralAngle => self.min_dihedral_angle,
+            QualityMetric::MaxDihedralAngle => self.max_dihedral_angle,
+        }
+    }
+}
+
+impl QualityMetric {
+    // true if `value` is a worse quality than `threshold`
+    pub fn is_worse(&self, value: f64, threshold: f64) -> bool {
+        match self {
+            QualityMetric::ScaledJacobian | QualityMetric::MinDihedralAngle => value < threshold,
+            _ => value > threshold,
+        }
+    }
+}
+
+// range and distribution of the values of a metric
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricStatistics {
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    // number of values in equal bins between min and max
+    pub histogram: Vec<usize>,
+}
+
+impl MetricStatistics {
+    fn new(values: &[f64], nb_bins: usize) -> Option<Self> {
+        let min = values.iter().copied().reduce(f64::min)?;
+        let max = values.iter().copied().reduce(f64::max)?;
+        let mut histogram = vec![0; nb_bins.max(1)];
+        let width = (max - min) / histogram.len() as f64;
+        for value in values {
+            let bin = match width > 0. {
+                true => ((value - min) / width) as usize,
+                false => 0,
+            };
+            histogram[bin.min(nb_bins.max(1) - 1)] += 1;
+        }
+        Some(MetricStatistics {
+            count: values.len(),
+            min,
+            max,
+            histogram,
+        })
+    }
+}
+
+// statistics of a metric per cell type and per group of cells
+#[derive(Debug, Clone, PartialEq)]
+pub struct QualityReport {
+    pub metric: QualityMetric,
+    pub by_type: HashMap<CellType, MetricStatistics>,
+    pub by_group: HashMap<String, MetricStatistics>,
+}
+
+fn sub(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+// angle between two vectors in degrees, 0 if one of them is null
+fn angle(a: [f64; 3], b: [f64; 3]) -> f64 {
+    let norms = norm(a) * norm(b);
+    if norms == 0. {
+        return 0.;
+    }
+    (dot(a, b) / norms).clamp(-1., 1.).acos().to_degrees()
+}
+
+// linear edges as pairs of local corner indices
+fn corner_edges(cell_type: &CellType) -> Vec<[usize; 2]> {
+    cell_type
+        .linear_type()
+        .edges()
+        .iter()
+        .map(|edge| [edge.nodes[0], edge.nodes[1]])
+        .collect()
+}
+
+fn aspect_ratio(cell_type: &CellType, coords: &[[f64; 3]]) -> Option<f64> {
+    let lengths: Vec<f64> = corner_edges(cell_type)
+        .iter()
+        .map(|[a, b]| norm(sub(coords[*b], coords[*a])))
+        .collect();
+    let min = lengths.iter().copied().reduce(f64::min)?;
+    let max = lengths.iter().copied().reduce(f64::max)?;
+    match min > 0. {
+        true => Some(max / min),
+        false => Some(f64::INFINITY),
+    }
+}
+
+// value of the corner determinant for the ideal cell
+fn ideal_corner_determinant(cell_type: &CellType) -> f64 {
+    match cell_type.linear_type() {
+        CellType::TETRA4 | CellType::PYRAM5 => FRAC_1_SQRT_2,
+        CellType::PENTA6 | CellType::TRIA3 => 3f64.sqrt() / 2.,
+        _ => 1.,
+    }
+}
+
+fn scaled_jacobian(cell_type: &CellType, coords: &[[f64; 3]], normal: [f64; 3]) -> Option<f64> {
+    let dim = cell_type.topological_dimension();
+    if dim < 2 {
+        return None;
+    }
+    let reference = reference_coordinates(&cell_type.linear_type());
+    let edges = corner_edges(cell_type);
+    let determinant = |points: &[[f64; 3]], corner: usize, neighbours: &[usize]| {
+        let vectors: Vec<[f64; 3]> = neighbours
+            .iter()
+            .map(|n| sub(points[*n], points[corner]))
+            .collect();
+        let lengths: f64 = vectors.iter().map(|v| norm(*v)).product();
+        let det = match dim {
+            2 => dot(cross(vectors[0], vectors[1]), normal),
+            _ => dot(cross(vectors[0], vectors[1]), vectors[2]),
+        };
+        (det, lengths)
+    };
+    let mut min: Option<f64> = None;
+    for corner in cell_type.corner_node_indices() {
+        let neighbours: Vec<usize> = edges
+            .iter()
+            .filter_map(|[a, b]| match (*a == corner, *b == corner) {
+                (true, _) => Some(*b),
+                (_, true) => Some(*a),
+                _ => None,
+            })
+            .collect();
+        // the apex of pyramids has 4 edges
+        if neighbours.len() != dim {
+            continue;
+        }
+        // orientation of the neighbours in the reference element
+        let reference_normal = [0., 0., 1.];
+        let (reference_det, _) = match dim {
+            2 => {
+                let v1 = sub(reference[neighbours[0]], reference[corner]);
+                let v2 = sub(reference[neighbours[1]], reference[corner]);
+                (dot(cross(v1, v2), reference_normal), 0.)
+            }
+            _ => determinant(&reference, corner, &neighbours),
+        };
+        let (det, lengths) = determinant(coords, corner, &neighbours);
+        let value = match lengths > 0. {
+            true => reference_det.signum() * det / lengths / ideal_corner_determinant(cell_type),
+            false => 0.,
+        };
+        min = Some(min.map_or(value, |min: f64| min.min(value)));
+    }
+    min
+}
+
+// corner coordinates of the faces of a cell, the cell itself for surface cells
+fn face_polygons(cell_type: &CellType, coords: &[[f64; 3]]) -> Vec<Vec<[f64; 3]>> {
+    cell_type
+        .linear_type()
+        .faces()
+        .iter()
+        .map(|face| face.nodes.iter().map(|i| coords[*i]).collect())
+        .collect()
+}
+
+fn skewness(cell_type: &CellType, coords: &[[f64; 3]]) -> Option<f64> {
+    let mut skewness: Option<f64> = None;
+    for polygon in face_polygons(cell_type, coords) {
+        let n = polygon.len();
+        let ideal = 180. * (n as f64 - 2.) / n as f64;
+        for i in 0..n {
+            let previous = sub(polygon[(i + n - 1) % n], polygon[i]);
+            let next = sub(polygon[(i + 1) % n], polygon[i]);
+            let theta = angle(previous, next);
+            let value = ((theta - ideal) / (180. - ideal)).max((ideal - theta) / ideal);
+            skewness = Some(skewness.map_or(value, |skewness: f64| skewness.max(value)));
+        }
+    }
+    skewness
+}
+
+fn warpage(cell_type: &CellType, coords: &[[f64; 3]]) -> Option<f64> {
+    if cell_type.linear_type() != CellType::QUAD4 {
+        return None;
+    }
+    let split = |a: usize, b: usize, c: usize, d: usize| {
+        let n1 = cross(sub(coords[b], coords[a]), sub(coords[c], coords[a]));
+        let n2 = cross(sub(coords[c], coords[a]), sub(coords[d], coords[a]));
+        angle(n1, n2)
+    };
+    Some(split(0, 1, 2, 3).max(split(1, 2, 3, 0)))
+}
+
+// smallest and largest angles between the faces of a tetrahedron
+fn dihedral_angles(cell_type: &CellType, coords: &[[f64; 3]]) -> Option<(f64, f64)> {
+    if cell_type.linear_type() != CellType::TETRA4 {
+        return None;
+    }
+    let faces = cell_type.linear_type().faces();
+    let normals: Vec<[f64; 3]> = faces
+        .iter()
+        .map(|face| {
+            let [a, b, c] = [face.nodes[0], face.nodes[1], face.nodes[2]];
+            cross(sub(coords[b], coords[a]), sub(coords[c], coords[a]))
+        })
+        .collect();
+    let mut angles = vec![];
+    for [a, b] in corner_edges(cell_type) {
+        let adjacent: Vec<usize> = (0..faces.len())
+            .filter(|f| faces[*f].nodes.contains(&a) && faces[*f].nodes.contains(&b))
+            .collect();
+        angles.push(180. - angle(normals[adjacent[0]], normals[adjacent[1]]));
+    }
+    let min = angles.iter().copied().reduce(f64::min)?;
+    let max = angles.iter().copied().reduce(f64::max)?;
+    Some((min, max))
+}
+
+impl Mesh {
+    pub fn cell_quality(&self, cell_id: usize) -> Result<CellQuality, MeshError> {
+        let coords = self.cell_coordinates(cell_id)?;
+        let cell_type = &self.cells[&cell_id].ty;
+        let coords = &coords[..cell_type.get_nb_of_corner_nodes()];
+        // surface cells of a 2D mesh are oriented along z, other ones along their mean normal
+        let normal = match (self.dim, cell_type.topological_dimension()) {
+            (2, 2) => [0., 0., 1.],
+            (_, 2) => {
+                let n = coords.len();
+                let mut normal = [0.; 3];
+                for i in 0..n {
+                    let previous = sub(coords[(i + n - 1) % n], coords[i]);
+                    let next = sub(coords[(i + 1) % n], coords[i]);
+                    let corner_normal = cross(next, previous);
+                    for k in 0..3 {
+                        normal[k] += corner_normal[k];
+                    }
+                }
+                let length = norm(normal);
+                match length > 0. {
+                    true => normal.map(|value| value / length),
+                    false => normal,
+                }
+            }
+            _ => [0.; 3],
+        };
+        let dihedral = dihedral_angles(cell_type, coords);
+        Ok(CellQuality {
+            aspect_ratio: aspect_ratio(cell_type, coords),
+            scaled_jacobian: scaled_jacobian(cell_type, coords, normal),
+            skewness: skewness(cell_type, coords),
+            warpage: warpage(cell_type, coords),
+            min_dihedral_angle: dihedral.map(|(min, _)| min),
+            max_dihedral_angle: dihedral.map(|(_, max)| max),
+        })
+    }
+
+    // value of the metric for every cell where it applies
+    fn metric_values(&self, metric: QualityMetric) -> Result<HashMap<usize, f64>, MeshError> {
+        let mut values = HashMap::new();
+        for cell_id in self.cells.keys() {
+            if let Some(value) = self.cell_quality(*cell_id)?.get(metric) {
+                values.insert(*cell_id, value);
+            }
+        }
+        Ok(values)
+    }
+
+    // statistics of a metric per cell type and per group of cells,
+    // with histograms of `nb_bins` bins
+    pub fn quality_report(
+        &self,
+        metric: QualityMetric,
+        nb_bins: usize,
+    ) -> Result<QualityReport, MeshError> {
+        let values = self.metric_values(metric)?;
+        let mut by_type: HashMap<CellType, Vec<f64>> = HashMap::new();
+        for (cell_id, value) in values.iter() {
+            by_type
+                .entry(self.cells[cell_id].ty.clone())
+                .or_default()
+                .push(*value);
+        }
+        let by_group = self.gma.iter().filter_map(|(name, cell_ids)| {
+            let group_values: Vec<f64> = cell_ids
+                .iter()
+                .filter_map(|cell_id| values.get(cell_id))
+                .copied()
+                .collect();
+            MetricStatistics::new(&group_values, nb_bins).map(|stats| (name.to_string(), stats))
+        });
+        Ok(QualityReport {
+            metric,
+            by_group: by_group.collect(),
+            by_type: by_type
+                .into_iter()
+                .filter_map(|(ty, values)| MetricStatistics::new(&values, nb_bins).map(|s| (ty, s)))
+                .collect(),
+        })
+    }
+
+    // Stores the cells whose metric is worse than the threshold in a new group of cells,
+    // returns their ids
+    pub fn flag_poor_quality_cells(
+        &mut self,
+        metric: QualityMetric,
+        threshold: f64,
+        group: &str,
+    ) -> Result<Vec<usize>, MeshError> {
+        if self.gma.contains_key(group) {
+            return Err(MeshError::DuplicateCellGroup(group.to_string()));
+        }
+        let mut cell_ids: Vec<usize> = self
+            .metric_values(metric)?
+            .into_iter()
+            .filter(|(_, value)| metric.is_worse(*value, threshold))
+            .map(|(cell_id, _)| cell_id)
+            .collect();
+        cell_ids.sort();
+        self.create_cell_group(group, &cell_ids)?;
+        Ok(cell_ids)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::{CellType, Node};
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::QualityMetric;
+    use crate::mesh_errors::MeshError;
+
+    fn add_cell(mesh: &mut Mesh, cell_type: CellType, points: &[[f64; 3]]) -> usize {
+        let node_ids: Vec<usize> = points
+            .iter()
+            .map(|[x, y, z]| {
+                mesh.add_a_node(
+                    Node {
+                        x: *x,
+                        y: *y,
+                        z: *z,
+                    },
+                    None,
+                )
+                .unwrap()
+            })
+            .collect();
+        mesh.add_a_cell(cell_type, &node_ids).unwrap()
+    }
+
+    fn assert_close(value: Option<f64>, expected: f64) {
+        let value = value.unwrap();
+        assert!(
+            (value - expected).abs() < 1e-10,
+            "{} != {}",
+            value,
+            expected
+        );
+    }
+
+    const UNIT_CUBE: [[f64; 3]; 8] = [
+        [0., 0., 0.],
+        [1., 0., 0.],
+        [1., 1., 0.],
+        [0., 1., 0.],
+        [0., 0., 1.],
+        [1., 0., 1.],
+        [1., 1., 1.],
+        [0., 1., 1.],
+    ];
+
+    // regular tetrahedron with the Code_aster orientation
+    fn regular_tetra() -> Vec<[f64; 3]> {
+        let h = (2f64 / 3.).sqrt();
+        vec![
+            [0.5, 3f64.sqrt() / 6., h],
+            [1., 0., 0.],
+            [0., 0., 0.],
+            [0.5, 3f64.sqrt() / 2., 0.],
+        ]
+    }
+
+    #[test]
+    fn ideal_cells_should_have_ideal_quality() {
+        let mut mesh = Mesh::new();
+        let hexa = add_cell(&mut mesh, CellType::HEXA8, &UNIT_CUBE);
+        let quality = mesh.cell_quality(hexa).unwrap();
+        assert_close(quality.aspect_ratio, 1.);
+        assert_close(quality.scaled_jacobian, 1.);
+        assert_close(quality.skewness, 0.);
+        assert_eq!(quality.warpage, None);
+        assert_eq!(quality.min_dihedral_angle, None);
+
+        let tetra = add_cell(&mut mesh, CellType::TETRA4, &regular_tetra());
+        let quality = mesh.cell_quality(tetra).unwrap();
+        assert_close(quality.aspect_ratio, 1.);
+        assert_close(quality.scaled_jacobian, 1.);
+        assert_close(quality.skewness, 0.);
+        let dihedral = (1f64 / 3.).acos().to_degrees();
+        assert_close(quality.min_dihedral_angle, dihedral);
+        assert_close(quality.max_dihedral_angle, dihedral);
+
+        let quad = add_cell(&mut mesh, CellType::QUAD4, &UNIT_CUBE[..4]);
+        let quality = mesh.cell_quality(quad).unwrap();
+        assert_close(quality.warpage, 0.);
+        assert_close(quality.scaled_jacobian, 1.);
+    }
+
+    #[test]
+    fn distorted_cells_should_have_worse_quality() {
+        let mut mesh = Mesh::new();
+        let warped = add_cell(
+            &mut mesh,
+            CellType::QUAD4,
+            &[[0., 0., 0.], [2., 0., 0.], [2., 1., 1.], [0., 1., 0.]],
+        );
+        let quality = mesh.cell_quality(warped).unwrap();
+        assert!(quality.warpage.unwrap() > 10.);
+        assert_close(quality.aspect_ratio, 5f64.sqrt());
+        // a flat tetrahedron
+        let flat = add_cell(
+            &mut mesh,
+            CellType::TETRA4,
+            &[[0.3, 0.3, 1e-3], [1., 0., 0.], [0., 0., 0.], [0., 1., 0.]],
+        );
+        let quality = mesh.cell_quality(flat).unwrap();
+        assert!(quality.scaled_jacobian.unwrap() < 0.01);
+        assert!(quality.max_dihedral_angle.unwrap() > 170.);
+        // an inverted hexahedron
+        let mut inverted = UNIT_CUBE;
+        inverted.swap(1, 3);
+        inverted.swap(5, 7);
+        let hexa = add_cell(&mut mesh, CellType::HEXA8, &inverted);
+        assert_close(mesh.cell_quality(hexa).unwrap().scaled_jacobian, -1.);
+    }
+
+    #[test]
+    fn quality_report_should_group_statistics() {
+        let mut mesh = Mesh::new();
+        mesh.dim = 2;
+        let square = add_cell(&mut mesh, CellType::QUAD4, &UNIT_CUBE[..4]);
+        let long = add_cell(
+            &mut mesh,
+            CellType::QUAD4,
+            &[[0., 0., 0.], [4., 0., 0.], [4., 1., 0.], [0., 1., 0.]],
+        );
+        let triangle = add_cell(
+            &mut mesh,
+            CellType::TRIA3,
+            &[[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+        );
+        mesh.create_cell_group("PLATE", &[long, triangle]).unwrap();
+
+        let report = mesh.quality_report(QualityMetric::AspectRatio, 3).unwrap();
+        let quads = &report.by_type[&CellType::QUAD4];
+        assert_eq!(quads.count, 2);
+        assert_eq!((quads.min, quads.max), (1., 4.));
+        assert_eq!(quads.histogram, vec![1, 0, 1]);
+        let plate = &report.by_group["PLATE"];
+        assert_eq!(plate.count, 2);
+        assert_eq!(plate.max, 4.);
+
+        let flagged = mesh
+            .flag_poor_quality_cells(QualityMetric::AspectRatio, 1.2, "POOR")
+            .unwrap();
+        assert_eq!(flagged, vec![long, triangle]);
+        assert_eq!(mesh.get_cell_group("POOR").unwrap(), &[long, triangle]);
+        assert!(!flagged.contains(&square));
+        assert!(matches!(
+            mesh.flag_poor_quality_cells(QualityMetric::Skewness, 0.5, "POOR"),
+            Err(MeshError::DuplicateCellGroup(_))
+        ));
+    }
+}
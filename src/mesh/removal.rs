@@ -0,0 +1,176 @@
+use std::collections::HashSet;
+
+use crate::mesh::Mesh;
+use crate::mesh_enums::NodeRemovalPolicy;
+use crate::mesh_errors::MeshError;
+
+// sorted ids without duplicates
+fn unique_ids(ids: &[usize]) -> Vec<usize> {
+    let mut ids = ids.to_vec();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+impl Mesh {
+    // removes cells from mesh, groups of cells and names.
+    // Nothing is removed if a cell id is unknown
+    pub fn remove_cells(&mut self, cell_ids: &[usize]) -> Result<(), MeshError> {
+        self.check_cell_ids(cell_ids)?;
+        let cell_ids = unique_ids(cell_ids);
+        for cell_id in cell_ids.iter() {
+            self.cells.remove(cell_id);
+        }
+        self.remove_cells_from_groups(&cell_ids);
+        self.forget_cell_names(&cell_ids);
+        Ok(())
+    }
+
+    // removes nodes from mesh, groups of nodes and names.
+    // Cells referencing a removed node are refused or removed depending on the policy,
+    // the ids of the removed cells are returned.
+    // Nothing is removed if an error is returned
+    pub fn remove_nodes(
+        &mut self,
+        node_ids: &[usize],
+        policy: NodeRemovalPolicy,
+    ) -> Result<Vec<usize>, MeshError> {
+        self.check_node_ids(node_ids)?;
+        let node_ids = unique_ids(node_ids);
+        let removed: HashSet<&usize> = node_ids.iter().collect();
+        let mut cell_ids: Vec<usize> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| cell.co.iter().any(|node_id| removed.contains(node_id)))
+            .map(|(cell_id, _)| *cell_id)
+            .collect();
+        cell_ids.sort();
+
+        if let (NodeRemovalPolicy::Refuse, Some(cell_id)) = (policy, cell_ids.first()) {
+            let cell = &self.cells[cell_id];
+            let node_id = cell.co.iter().find(|node_id| removed.contains(node_id));
+            return Err(MeshError::NodeInUse {
+                node_id: *node_id.unwrap_or(&node_ids[0]),
+                cell_id: *cell_id,
+            });
+        }
+        self.remove_cells(&cell_ids)?;
+        for node_id in node_ids.iter() {
+            self.nodes.remove(node_id);
+        }
+        self.remove_nodes_from_groups(&node_ids);
+        self.forget_node_names(&node_ids);
+        Ok(cell_ids)
+    }
+
+    // removes the nodes which are not referenced by any cell, returns their ids.
+    // Nodes only referenced by groups of nodes are removed too
+    pub fn remove_orphan_nodes(&mut self) -> Vec<usize> {
+        let used: HashSet<&usize> = self.cells.values().flat_map(|cell| cell.co.iter()).collect();
+        let mut orphans: Vec<usize> = self
+            .nodes
+            .keys()
+            .filter(|node_id| !used.contains(node_id))
+            .copied()
+            .collect();
+        orphans.sort();
+        for node_id in orphans.iter() {
+            self.nodes.remove(node_id);
+        }
+        self.remove_nodes_from_groups(&orphans);
+        self.forget_node_names(&orphans);
+        orphans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::NodeRemovalPolicy;
+    use crate::mesh_errors::MeshError;
+
+    fn get_mesh_with_groups() -> Mesh {
+        let content = r#"COOR_3D
+N1 0. 0. 0.
+N2 1. 0. 0.
+N3 1. 1. 0.
+N4 0. 1. 0.
+N5 2. 2. 0.
+FINSF
+SEG2
+M1 N1 N2
+M2 N2 N3
+M3 N3 N4
+FINSF
+GROUP_NO BOTTOM N1 N2 N5
+FINSF
+GROUP_MA EDGES M1 M2
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn remove_cells_should_clean_groups_and_names() {
+        let mut mesh = get_mesh_with_groups();
+        mesh.remove_cells(&[1, 0, 1]).unwrap();
+        assert_eq!(mesh.cells.len(), 1);
+        assert!(mesh.get_cell_group("EDGES").unwrap().is_empty());
+        assert!(mesh.cell_id_by_name("M1").is_err());
+        assert_eq!(mesh.cell_id_by_name("M3").unwrap(), 2);
+        // the name of a removed cell can be used again
+        let cell_id = mesh.add_a_named_cell(crate::lib::CellType::SEG2, &[0, 1], "M1").unwrap();
+        assert_eq!(mesh.cell_id_by_name("M1").unwrap(), cell_id);
+    }
+
+    #[test]
+    fn remove_unknown_cells_should_fail_without_removing_anything() {
+        let mut mesh = get_mesh_with_groups();
+        assert!(matches!(mesh.remove_cells(&[0, 7]), Err(MeshError::UnknownCellId(7))));
+        assert_eq!(mesh.cells.len(), 3);
+        assert_eq!(mesh.get_cell_group("EDGES").unwrap(), &[0, 1]);
+    }
+
+    #[test]
+    fn remove_nodes_should_refuse_referenced_nodes() {
+        let mut mesh = get_mesh_with_groups();
+        assert!(matches!(
+            mesh.remove_nodes(&[4, 2], NodeRemovalPolicy::Refuse),
+            Err(MeshError::NodeInUse { node_id: 2, cell_id: 1 })
+        ));
+        assert_eq!(mesh.nodes.len(), 5);
+        assert_eq!(mesh.remove_nodes(&[4], NodeRemovalPolicy::Refuse).unwrap(), vec![]);
+        assert_eq!(mesh.get_node_group("BOTTOM").unwrap(), &[0, 1]);
+        assert!(mesh.node_id_by_name("N5").is_err());
+    }
+
+    #[test]
+    fn remove_nodes_should_cascade_to_cells() {
+        let mut mesh = get_mesh_with_groups();
+        let removed = mesh.remove_nodes(&[1], NodeRemovalPolicy::Cascade).unwrap();
+        assert_eq!(removed, vec![0, 1]);
+        assert_eq!(mesh.nodes.len(), 4);
+        assert_eq!(mesh.cells.len(), 1);
+        assert_eq!(mesh.get_node_group("BOTTOM").unwrap(), &[0, 4]);
+        assert!(mesh.get_cell_group("EDGES").unwrap().is_empty());
+        assert!(mesh.node_id_by_name("N2").is_err());
+        assert!(mesh.cell_id_by_name("M2").is_err());
+        assert!(matches!(
+            mesh.remove_nodes(&[1], NodeRemovalPolicy::Cascade),
+            Err(MeshError::UnknownNodeId(1))
+        ));
+    }
+
+    #[test]
+    fn remove_orphan_nodes_should_work() {
+        let mut mesh = get_mesh_with_groups();
+        mesh.remove_cells(&[2]).unwrap();
+        assert_eq!(mesh.remove_orphan_nodes(), vec![3, 4]);
+        assert_eq!(mesh.nodes.len(), 3);
+        assert_eq!(mesh.get_node_group("BOTTOM").unwrap(), &[0, 1]);
+        assert!(mesh.node_id_by_name("N4").is_err());
+        assert!(mesh.remove_orphan_nodes().is_empty());
+    }
+}
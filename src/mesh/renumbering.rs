@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+
+use crate::mesh::Mesh;
+
+// old id -> new id tables of a renumbering
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct IdMapping {
+    pub nodes: HashMap<usize, usize>,
+    pub cells: HashMap<usize, usize>,
+}
+
+// maps every sorted id to its rank
+fn dense_mapping<T>(map: &HashMap<usize, T>) -> HashMap<usize, usize> {
+    let mut ids: Vec<usize> = map.keys().copied().collect();
+    ids.sort();
+    ids.into_iter()
+        .enumerate()
+        .map(|(new_id, old_id)| (old_id, new_id))
+        .collect()
+}
+
+fn renumber_keys<T>(map: &mut HashMap<usize, T>, mapping: &HashMap<usize, usize>) {
+    *map = map
+        .drain()
+        .map(|(old_id, value)| (mapping.get(&old_id).copied().unwrap_or(old_id), value))
+        .collect();
+}
+
+fn renumber_values<K>(map: &mut HashMap<K, usize>, mapping: &HashMap<usize, usize>) {
+    for id in map.values_mut() {
+        if let Some(new_id) = mapping.get(id) {
+            *id = *new_id;
+        }
+    }
+}
+
+fn renumber_groups(groups: &mut HashMap<Box<str>, Vec<usize>>, mapping: &HashMap<usize, usize>) {
+    for group in groups.values_mut() {
+        for id in group.iter_mut() {
+            if let Some(new_id) = mapping.get(id) {
+                *id = *new_id;
+            }
+        }
+        group.sort();
+    }
+}
+
+impl Mesh {
+    // gives nodes and cells the contiguous ids 0..n, keeping their order.
+    // Connectivities, groups and names are updated, returns old id -> new id tables
+    pub fn compact(&mut self) -> IdMapping {
+        let mapping = IdMapping {
+            nodes: dense_mapping(&self.nodes),
+            cells: dense_mapping(&self.cells),
+        };
+        self.renumber(&mapping);
+        mapping
+    }
+
+    // applies a renumbering to every id stored in mesh, ids missing from the
+    // tables are kept. New ids must not collide with kept ids
+    pub(crate) fn renumber(&mut self, mapping: &IdMapping) {
+        renumber_keys(&mut self.nodes, &mapping.nodes);
+        renumber_keys(&mut self.cells, &mapping.cells);
+        for cell in self.cells.values_mut() {
+            for node_id in cell.co.iter_mut() {
+                if let Some(new_id) = mapping.nodes.get(node_id) {
+                    *node_id = *new_id;
+                }
+            }
+        }
+        renumber_groups(&mut self.gno, &mapping.nodes);
+        renumber_groups(&mut self.gma, &mapping.cells);
+        renumber_keys(&mut self.nodes_id_to_name, &mapping.nodes);
+        renumber_values(&mut self.nodes_name_to_id, &mapping.nodes);
+        renumber_keys(&mut self.cells_id_to_name, &mapping.cells);
+        renumber_values(&mut self.cells_name_to_id, &mapping.cells);
+        self.next_node_id = self.nodes.keys().max().map_or(0, |id| id + 1);
+        self.next_cell_id = self.cells.keys().max().map_or(0, |id| id + 1);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::NodeRemovalPolicy;
+
+    fn get_mesh_with_holes() -> Mesh {
+        let content = r#"COOR_3D
+N1 0. 0. 0.
+N2 1. 0. 0.
+N3 1. 1. 0.
+N4 0. 1. 0.
+FINSF
+SEG2
+M1 N1 N2
+M2 N2 N3
+M3 N3 N4
+M4 N4 N1
+FINSF
+GROUP_NO CORNERS N3 N4
+FINSF
+GROUP_MA EDGES M3 M4
+FINSF
+FIN"#;
+        let mut mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        mesh.remove_nodes(&[1], NodeRemovalPolicy::Cascade).unwrap();
+        mesh
+    }
+
+    #[test]
+    fn compact_should_give_contiguous_ids() {
+        let mut mesh = get_mesh_with_holes();
+        let mapping = mesh.compact();
+        assert_eq!(mapping.nodes[&0], 0);
+        assert_eq!(mapping.nodes[&2], 1);
+        assert_eq!(mapping.nodes[&3], 2);
+        assert_eq!(mapping.cells[&2], 0);
+        assert_eq!(mapping.cells[&3], 1);
+        assert_eq!(mesh.next_node_id, 3);
+        assert_eq!(mesh.next_cell_id, 2);
+        let mut node_ids: Vec<&usize> = mesh.nodes.keys().collect();
+        node_ids.sort();
+        assert_eq!(node_ids, vec![&0, &1, &2]);
+        assert_eq!(mesh.cells[&0].co, vec![1, 2]);
+        assert_eq!(mesh.cells[&1].co, vec![2, 0]);
+    }
+
+    #[test]
+    fn compact_should_update_groups_and_names() {
+        let mut mesh = get_mesh_with_holes();
+        mesh.compact();
+        assert_eq!(mesh.get_node_group("CORNERS").unwrap(), &[1, 2]);
+        assert_eq!(mesh.get_cell_group("EDGES").unwrap(), &[0, 1]);
+        assert_eq!(mesh.node_id_by_name("N3").unwrap(), 1);
+        assert_eq!(mesh.node_name(2).unwrap(), "N4");
+        assert_eq!(mesh.cell_name(0).unwrap(), "M3");
+        assert_eq!(mesh.cell_id_by_name("M4").unwrap(), 1);
+    }
+
+    #[test]
+    fn compact_should_keep_a_dense_mesh_unchanged() {
+        let mut mesh = get_mesh_with_holes();
+        mesh.compact();
+        let mapping = mesh.compact();
+        assert!(mapping.nodes.iter().all(|(old_id, new_id)| old_id == new_id));
+        assert!(mapping.cells.iter().all(|(old_id, new_id)| old_id == new_id));
+    }
+}
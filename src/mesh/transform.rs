@@ -0,0 +1,353 @@
+use std::collections::HashSet;
+
+use crate::mesh::Mesh;
+use crate::mesh_errors::MeshError;
+use crate::node::Node;
+
+// Affine transformation of the space in homogeneous coordinates: a point p becomes
+// `matrix * [p.x, p.y, p.z, 1]`. The last row is expected to be [0, 0, 0, 1]
+#[derive(Debug, Clone, PartialEq)]
+pub struct AffineTransform {
+    pub matrix: [[f64; 4]; 4],
+}
+
+impl Default for AffineTransform {
+    fn default() -> Self {
+        AffineTransform::identity()
+    }
+}
+
+fn unit(vector: &Node) -> Result<[f64; 3], MeshError> {
+    let length = (vector.x * vector.x + vector.y * vector.y + vector.z * vector.z).sqrt();
+    // NaN lengths are refused too
+    if length.is_nan() || length <= f64::EPSILON {
+        return Err(MeshError::DegenerateVector(vector.clone()));
+    }
+    Ok([vector.x / length, vector.y / length, vector.z / length])
+}
+
+// transform with the given linear part and no translation
+fn linear(part: [[f64; 3]; 3]) -> AffineTransform {
+    let mut transform = AffineTransform::identity();
+    for (row, values) in transform.matrix.iter_mut().zip(part) {
+        row[..3].copy_from_slice(&values);
+    }
+    transform
+}
+
+impl AffineTransform {
+    pub fn identity() -> Self {
+        let mut matrix = [[0.; 4]; 4];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            row[i] = 1.;
+        }
+        AffineTransform { matrix }
+    }
+
+    pub fn translation(vector: &Node) -> Self {
+        let mut transform = AffineTransform::identity();
+        transform.matrix[0][3] = vector.x;
+        transform.matrix[1][3] = vector.y;
+        transform.matrix[2][3] = vector.z;
+        transform
+    }
+
+    // rotation of `angle` radians around an axis through the origin, counterclockwise
+    // when seen from the tip of the axis. The axis does not need to be normalised but
+    // must be longer than f64::EPSILON
+    pub fn rotation(axis: &Node, angle: f64) -> Result<Self, MeshError> {
+        let [x, y, z] = unit(axis)?;
+        let (sin, cos) = angle.sin_cos();
+        let c = 1. - cos;
+        Ok(linear([
+            [cos + x * x * c, x * y * c - z * sin, x * z * c + y * sin],
+            [y * x * c + z * sin, cos + y * y * c, y * z * c - x * sin],
+            [z * x * c - y * sin, z * y * c + x * sin, cos + z * z * c],
+        ]))
+    }
+
+    // rotation of `angle` radians around an axis through `center`
+    pub fn rotation_about(center: &Node, axis: &Node, angle: f64) -> Result<Self, MeshError> {
+        Ok(AffineTransform::about(
+            center,
+            AffineTransform::rotation(axis, angle)?,
+        ))
+    }
+
+    // scaling by the same factor along every axis, centred on the origin
+    pub fn scaling(factor: f64) -> Self {
+        AffineTransform::scaling_per_axis(factor, factor, factor)
+    }
+
+    // scaling by a factor along each axis, centred on the origin
+    pub fn scaling_per_axis(x: f64, y: f64, z: f64) -> Self {
+        linear([[x, 0., 0.], [0., y, 0.], [0., 0., z]])
+    }
+
+    // reflection across the plane through `point` orthogonal to `normal`
+    pub fn mirror(point: &Node, normal: &Node) -> Result<Self, MeshError> {
+        let n = unit(normal)?;
+        let mut part = [[0.; 3]; 3];
+        for i in 0..3 {
+            for j in 0..3 {
+                part[i][j] = if i == j { 1. } else { 0. } - 2. * n[i] * n[j];
+            }
+        }
+        Ok(AffineTransform::about(point, linear(part)))
+    }
+
+    // `transform` applied with `center` as origin
+    fn about(center: &Node, transform: AffineTransform) -> Self {
+        let to_origin = AffineTransform::translation(&Node {
+            x: -center.x,
+            y: -center.y,
+            z: -center.z,
+        });
+        to_origin
+            .then(&transform)
+            .then(&AffineTransform::translation(center))
+    }
+
+    // transform applying `self` first, then `other`
+    pub fn then(&self, other: &AffineTransform) -> Self {
+        let mut matrix = [[0.; 4]; 4];
+        for (i, row) in matrix.iter_mut().enumerate() {
+            for (j, value) in row.iter_mut().enumerate() {
+                *value = (0..4).map(|k| other.matrix[i][k] * self.matrix[k][j]).sum();
+            }
+        }
+        AffineTransform { matrix }
+    }
+
+    pub fn apply(&self, node: &Node) -> Node {
+        let m = &self.matrix;
+        let row = |i: usize| m[i][0] * node.x + m[i][1] * node.y + m[i][2] * node.z + m[i][3];
+        Node {
+            x: row(0),
+            y: row(1),
+            z: row(2),
+        }
+    }
+
+    // determinant of the linear part, negative if the transform reverses orientations
+    pub fn determinant(&self) -> f64 {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+}
+
+impl Mesh {
+    // Moves the given nodes, the mesh dimension is raised if they leave the plane or the
+    // line of the mesh. If the transform reverses orientations, surface and volume
+    // cells whose nodes are all moved are reversed so that volumes stay positive
+    fn transform_nodes(&mut self, node_ids: &[usize], transform: &AffineTransform) {
+        for node_id in node_ids {
+            if let Some(node) = self.nodes.get_mut(node_id) {
+                *node = transform.apply(node);
+                if node.z != 0. {
+                    self.dim = self.dim.max(3);
+                } else if node.y != 0. {
+                    self.dim = self.dim.max(2);
+                }
+            }
+        }
+        if transform.determinant() >= 0. {
+            return;
+        }
+        let moved: HashSet<&usize> = node_ids.iter().collect();
+        let mut reversed: Vec<usize> = self
+            .cells
+            .iter()
+            .filter(|(_, cell)| {
+                cell.ty.topological_dimension() >= 2
+                    && cell.co.iter().all(|node_id| moved.contains(node_id))
+            })
+            .map(|(cell_id, _)| *cell_id)
+            .collect();
+        reversed.sort();
+        for cell_id in reversed {
+            // the cell ids come from the mesh
+            let _ = self.reverse_cell(cell_id);
+        }
+    }
+
+    pub fn transform(&mut self, transform: &AffineTransform) {
+        let node_ids: Vec<usize> = self.nodes.keys().copied().collect();
+        self.transform_nodes(&node_ids, transform);
+    }
+
+    pub fn transform_node_group(
+        &mut self,
+        name: &str,
+        transform: &AffineTransform,
+    ) -> Result<(), MeshError> {
+        let node_ids = self.get_node_group(name)?.to_vec();
+        self.check_node_ids(&node_ids)?;
+        self.transform_nodes(&node_ids, transform);
+        Ok(())
+    }
+
+    // transforms the nodes of the cells of the group
+    pub fn transform_cell_group(
+        &mut self,
+        name: &str,
+        transform: &AffineTransform,
+    ) -> Result<(), MeshError> {
+        let mut node_ids: Vec<usize> = vec![];
+        for cell_id in self.get_cell_group(name)? {
+            let cell = self
+                .cells
+                .get(cell_id)
+                .ok_or(MeshError::UnknownCellId(*cell_id))?;
+            node_ids.extend(cell.co.iter());
+        }
+        node_ids.sort();
+        node_ids.dedup();
+        self.check_node_ids(&node_ids)?;
+        self.transform_nodes(&node_ids, transform);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::f64::consts::FRAC_PI_2;
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::mail_writer::mail_writer;
+    use crate::lib::{CellType, Node};
+    use crate::mesh::transform::AffineTransform;
+    use crate::mesh::Mesh;
+    use crate::mesh_errors::MeshError;
+
+    fn node(x: f64, y: f64, z: f64) -> Node {
+        Node { x, y, z }
+    }
+
+    // unit cube with a face on its bottom
+    fn get_cube() -> Mesh {
+        let mut mesh = Mesh::new();
+        let points = [
+            [0., 0., 0.],
+            [1., 0., 0.],
+            [1., 1., 0.],
+            [0., 1., 0.],
+            [0., 0., 1.],
+            [1., 0., 1.],
+            [1., 1., 1.],
+            [0., 1., 1.],
+        ];
+        let node_ids: Vec<usize> = points
+            .iter()
+            .map(|[x, y, z]| mesh.add_a_node(node(*x, *y, *z), None).unwrap())
+            .collect();
+        mesh.add_a_cell(CellType::HEXA8, &node_ids).unwrap();
+        mesh.add_a_cell(CellType::QUAD4, &[0, 3, 2, 1]).unwrap();
+        mesh.create_node_group("TOP", &[4, 5, 6, 7]).unwrap();
+        mesh
+    }
+
+    #[test]
+    fn elementary_transforms_should_work() {
+        let point = node(1., 2., 3.);
+        assert_eq!(
+            AffineTransform::translation(&node(1., -1., 0.5)).apply(&point),
+            node(2., 1., 3.5)
+        );
+        let rotation = AffineTransform::rotation(&node(0., 0., 2.), FRAC_PI_2).unwrap();
+        assert_eq!(rotation.apply(&point), node(-2., 1., 3.));
+        let rotation =
+            AffineTransform::rotation_about(&node(1., 1., 0.), &node(0., 0., 1.), FRAC_PI_2)
+                .unwrap();
+        assert_eq!(rotation.apply(&point), node(0., 1., 3.));
+        assert_eq!(AffineTransform::scaling(2.).apply(&point), node(2., 4., 6.));
+        assert_eq!(
+            AffineTransform::scaling_per_axis(1., -1., 0.).apply(&point),
+            node(1., -2., 0.)
+        );
+        let mirror = AffineTransform::mirror(&node(0., 0., 1.), &node(0., 0., -3.)).unwrap();
+        assert_eq!(mirror.apply(&point), node(1., 2., -1.));
+        assert!(mirror.determinant() < 0.);
+        let general = AffineTransform {
+            matrix: [
+                [0., 1., 0., 1.],
+                [1., 0., 0., 0.],
+                [0., 0., 1., 0.],
+                [0., 0., 0., 1.],
+            ],
+        };
+        assert_eq!(general.apply(&point), node(3., 1., 3.));
+        let composed = general.then(&AffineTransform::scaling(2.));
+        assert_eq!(composed.apply(&point), node(6., 2., 6.));
+    }
+
+    #[test]
+    fn degenerate_vectors_should_be_refused() {
+        assert!(matches!(
+            AffineTransform::rotation(&node(0., 0., 0.), FRAC_PI_2),
+            Err(MeshError::DegenerateVector(_))
+        ));
+        assert!(matches!(
+            AffineTransform::rotation_about(&node(1., 1., 0.), &node(1e-17, 0., 0.), 1.),
+            Err(MeshError::DegenerateVector(_))
+        ));
+        assert!(matches!(
+            AffineTransform::mirror(&node(0., 0., 0.), &node(0., 0., 0.)),
+            Err(MeshError::DegenerateVector(_))
+        ));
+        assert!(matches!(
+            AffineTransform::rotation(&node(f64::NAN, 0., 1.), 1.),
+            Err(MeshError::DegenerateVector(_))
+        ));
+    }
+
+    #[test]
+    fn mirror_should_keep_volumes_positive() {
+        let mut mesh = get_cube();
+        mesh.transform(&AffineTransform::mirror(&node(0., 0., 0.), &node(1., 0., 0.)).unwrap());
+        assert_eq!(mesh.nodes[&1], node(-1., 0., 0.));
+        assert!((mesh.cell_measure(0).unwrap() - 1.).abs() < 1e-12);
+        assert_eq!(mesh.cells[&0].co, vec![0, 3, 2, 1, 4, 7, 6, 5]);
+        // the bottom face still points outwards
+        assert_eq!(mesh.cells[&1].co, vec![0, 1, 2, 3]);
+        assert!(mesh.find_defective_cells().unwrap().is_empty());
+    }
+
+    #[test]
+    fn transformed_nodes_should_be_written_in_the_new_dimension() {
+        let content = "COOR_2D\nN1 0. 1.\nN2 1. 1.\nFINSF\nSEG2\nM1 N1 N2\nFINSF\nFIN";
+        let mut mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        mesh.transform(&AffineTransform::translation(&node(1., 0., 0.)));
+        assert_eq!(mesh.dim, 2);
+        mesh.transform(&AffineTransform::rotation(&node(1., 0., 0.), FRAC_PI_2).unwrap());
+        assert_eq!(mesh.dim, 3);
+        let written = mail_writer(&mesh);
+        let reread = Mesh::create_from_parser_output(mail_parser(&written).unwrap()).unwrap();
+        assert_eq!(reread.nodes[&0], node(1., 0., 1.));
+        assert_eq!(reread.nodes[&1], node(2., 0., 1.));
+    }
+
+    #[test]
+    fn groups_should_be_transformed_alone() {
+        let mut mesh = get_cube();
+        mesh.transform_node_group("TOP", &AffineTransform::translation(&node(0., 0., 1.)))
+            .unwrap();
+        assert_eq!(mesh.nodes[&0], node(0., 0., 0.));
+        assert_eq!(mesh.nodes[&6], node(1., 1., 2.));
+        assert!((mesh.cell_measure(0).unwrap() - 2.).abs() < 1e-12);
+
+        mesh.create_cell_group("BOTTOM", &[1]).unwrap();
+        mesh.transform_cell_group("BOTTOM", &AffineTransform::scaling_per_axis(1., 1., -1.))
+            .unwrap();
+        // the hexahedron is not entirely mirrored, its nodes keep their order
+        assert_eq!(mesh.cells[&0].co, vec![0, 1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(mesh.cells[&1].co, vec![0, 1, 2, 3]);
+        assert!(matches!(
+            mesh.transform_node_group("MISSING", &AffineTransform::identity()),
+            Err(MeshError::UnknownNodeGroup(_))
+        ));
+    }
+}
@@ -0,0 +1,344 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+use crate::mesh::names::{is_valid_mail_group_name, is_valid_mail_name};
+use crate::mesh::Mesh;
+use crate::mesh_enums::{MeshFormat, ValidationIssue};
+
+// issues found by `Mesh::validate`, in the order of the checks
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ValidationReport {
+    pub issues: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.issues.is_empty()
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for issue in self.issues.iter() {
+            writeln!(f, "{}", issue)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::ConnectivitySize {
+                cell_id,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "cell_id {} expects {} nodes but has {}",
+                cell_id, expected, actual
+            ),
+            ValidationIssue::UnknownNode { cell_id, node_id } => {
+                write!(
+                    f,
+                    "cell_id {} uses node_id {} which is not in nodes",
+                    cell_id, node_id
+                )
+            }
+            ValidationIssue::UnknownNodeInGroup { group, node_id } => write!(
+                f,
+                "group of nodes {} contains node_id {} which is not in nodes",
+                group, node_id
+            ),
+            ValidationIssue::UnknownCellInGroup { group, cell_id } => write!(
+                f,
+                "group of cells {} contains cell_id {} which is not in cells",
+                group, cell_id
+            ),
+            ValidationIssue::DuplicateNodeName(name) => {
+                write!(f, "node name {} is given to several nodes", name)
+            }
+            ValidationIssue::DuplicateCellName(name) => {
+                write!(f, "cell name {} is given to several cells", name)
+            }
+            ValidationIssue::InvalidNodeName { node_id, name } => {
+                write!(f, "name {} of node_id {} is not valid", name, node_id)
+            }
+            ValidationIssue::InvalidCellName { cell_id, name } => {
+                write!(f, "name {} of cell_id {} is not valid", name, cell_id)
+            }
+            ValidationIssue::InvalidNodeGroupName(name) => {
+                write!(f, "group of nodes name {} is not valid", name)
+            }
+            ValidationIssue::InvalidCellGroupName(name) => {
+                write!(f, "group of cells name {} is not valid", name)
+            }
+            ValidationIssue::OrphanNode(node_id) => {
+                write!(f, "node_id {} is not used by any cell", node_id)
+            }
+            ValidationIssue::NextNodeIdTooLow {
+                next_node_id,
+                max_node_id,
+            } => write!(
+                f,
+                "next_node_id {} is not above node_id {}",
+                next_node_id, max_node_id
+            ),
+            ValidationIssue::NextCellIdTooLow {
+                next_cell_id,
+                max_cell_id,
+            } => write!(
+                f,
+                "next_cell_id {} is not above cell_id {}",
+                next_cell_id, max_cell_id
+            ),
+        }
+    }
+}
+
+fn is_valid_name(name: &str, format: &MeshFormat) -> bool {
+    match format {
+        MeshFormat::Mail => is_valid_mail_name(name),
+    }
+}
+
+fn is_valid_group_name(name: &str, format: &MeshFormat) -> bool {
+    match format {
+        MeshFormat::Mail => is_valid_mail_group_name(name),
+    }
+}
+
+fn sorted<T: Ord + Clone>(values: impl Iterator<Item = T>) -> Vec<T> {
+    let mut values: Vec<T> = values.collect();
+    values.sort();
+    values
+}
+
+// names stored for several ids
+fn duplicate_names(id_to_name: &HashMap<usize, Box<str>>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let duplicates: HashSet<String> = id_to_name
+        .values()
+        .filter(|name| !seen.insert(*name))
+        .map(|name| name.to_string())
+        .collect();
+    sorted(duplicates.into_iter())
+}
+
+impl Mesh {
+    // Checks every invariant expected by solvers and by the writer of `format`,
+    // all the issues found are reported
+    pub fn validate(&self, format: MeshFormat) -> ValidationReport {
+        let mut issues = vec![];
+        let cell_ids = sorted(self.cells.keys().copied());
+        let node_ids = sorted(self.nodes.keys().copied());
+
+        for cell_id in cell_ids.iter() {
+            let cell = &self.cells[cell_id];
+            let expected = cell.ty.get_nb_of_connectivities();
+            if cell.co.len() != expected {
+                issues.push(ValidationIssue::ConnectivitySize {
+                    cell_id: *cell_id,
+                    expected,
+                    actual: cell.co.len(),
+                });
+            }
+            for node_id in cell.co.iter() {
+                if !self.nodes.contains_key(node_id) {
+                    issues.push(ValidationIssue::UnknownNode {
+                        cell_id: *cell_id,
+                        node_id: *node_id,
+                    });
+                }
+            }
+        }
+
+        for group in sorted(self.gno.keys()) {
+            for node_id in self.gno[group].iter() {
+                if !self.nodes.contains_key(node_id) {
+                    issues.push(ValidationIssue::UnknownNodeInGroup {
+                        group: group.to_string(),
+                        node_id: *node_id,
+                    });
+                }
+            }
+        }
+        for group in sorted(self.gma.keys()) {
+            for cell_id in self.gma[group].iter() {
+                if !self.cells.contains_key(cell_id) {
+                    issues.push(ValidationIssue::UnknownCellInGroup {
+                        group: group.to_string(),
+                        cell_id: *cell_id,
+                    });
+                }
+            }
+        }
+
+        issues.extend(
+            duplicate_names(&self.nodes_id_to_name)
+                .into_iter()
+                .map(ValidationIssue::DuplicateNodeName),
+        );
+        issues.extend(
+            duplicate_names(&self.cells_id_to_name)
+                .into_iter()
+                .map(ValidationIssue::DuplicateCellName),
+        );
+        let node_names = self.node_names();
+        for node_id in node_ids.iter() {
+            if !is_valid_name(&node_names[node_id], &format) {
+                issues.push(ValidationIssue::InvalidNodeName {
+                    node_id: *node_id,
+                    name: node_names[node_id].clone(),
+                });
+            }
+        }
+        let cell_names = self.cell_names();
+        for cell_id in cell_ids.iter() {
+            if !is_valid_name(&cell_names[cell_id], &format) {
+                issues.push(ValidationIssue::InvalidCellName {
+                    cell_id: *cell_id,
+                    name: cell_names[cell_id].clone(),
+                });
+            }
+        }
+        for group in sorted(self.gno.keys()) {
+            if !is_valid_group_name(group, &format) {
+                issues.push(ValidationIssue::InvalidNodeGroupName(group.to_string()));
+            }
+        }
+        for group in sorted(self.gma.keys()) {
+            if !is_valid_group_name(group, &format) {
+                issues.push(ValidationIssue::InvalidCellGroupName(group.to_string()));
+            }
+        }
+
+        let used: HashSet<&usize> = self
+            .cells
+            .values()
+            .flat_map(|cell| cell.co.iter())
+            .collect();
+        for node_id in node_ids.iter() {
+            if !used.contains(node_id) {
+                issues.push(ValidationIssue::OrphanNode(*node_id));
+            }
+        }
+
+        if let Some(max_node_id) = node_ids.last() {
+            if self.next_node_id <= *max_node_id {
+                issues.push(ValidationIssue::NextNodeIdTooLow {
+                    next_node_id: self.next_node_id,
+                    max_node_id: *max_node_id,
+                });
+            }
+        }
+        if let Some(max_cell_id) = cell_ids.last() {
+            if self.next_cell_id <= *max_cell_id {
+                issues.push(ValidationIssue::NextCellIdTooLow {
+                    next_cell_id: self.next_cell_id,
+                    max_cell_id: *max_cell_id,
+                });
+            }
+        }
+        ValidationReport { issues }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::lib::mail_parser::mail_parser;
+    use crate::lib::{CellType, MeshCell, Node};
+    use crate::mesh::Mesh;
+    use crate::mesh_enums::{MeshFormat, ValidationIssue};
+
+    fn get_mesh() -> Mesh {
+        let content = r#"COOR_3D
+N1 0. 0. 0.
+N2 1. 0. 0.
+N3 1. 1. 0.
+FINSF
+TRIA3
+M1 N1 N2 N3
+FINSF
+GROUP_NO BOTTOM N1 N2
+FINSF
+GROUP_MA SURFACE M1
+FINSF
+FIN"#;
+        Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn parsed_mesh_should_be_valid() {
+        let report = get_mesh().validate(MeshFormat::Mail);
+        assert!(report.is_valid(), "{}", report);
+    }
+
+    #[test]
+    fn every_issue_should_be_reported() {
+        let mut mesh = get_mesh();
+        mesh.cells.insert(
+            5,
+            MeshCell {
+                ty: CellType::SEG2,
+                co: vec![0, 1, 7],
+            },
+        );
+        mesh.gno.insert("BAD-NAME".into(), vec![0, 9]);
+        mesh.gma.get_mut("SURFACE").unwrap().push(3);
+        // names can only be invalid when stored without the Mesh API
+        mesh.nodes_id_to_name.insert(1, "LONG_NAME".into());
+        mesh.nodes.insert(
+            4,
+            Node {
+                x: 2.,
+                y: 2.,
+                z: 0.,
+            },
+        );
+        mesh.next_node_id = 2;
+
+        let report = mesh.validate(MeshFormat::Mail);
+        assert_eq!(
+            report.issues,
+            vec![
+                ValidationIssue::ConnectivitySize {
+                    cell_id: 5,
+                    expected: 2,
+                    actual: 3
+                },
+                ValidationIssue::UnknownNode {
+                    cell_id: 5,
+                    node_id: 7
+                },
+                ValidationIssue::UnknownNodeInGroup {
+                    group: "BAD-NAME".to_string(),
+                    node_id: 9
+                },
+                ValidationIssue::UnknownCellInGroup {
+                    group: "SURFACE".to_string(),
+                    cell_id: 3
+                },
+                ValidationIssue::InvalidNodeName {
+                    node_id: 1,
+                    name: "LONG_NAME".to_string()
+                },
+                ValidationIssue::InvalidNodeGroupName("BAD-NAME".to_string()),
+                ValidationIssue::OrphanNode(4),
+                ValidationIssue::NextNodeIdTooLow {
+                    next_node_id: 2,
+                    max_node_id: 4
+                },
+                ValidationIssue::NextCellIdTooLow {
+                    next_cell_id: 1,
+                    max_cell_id: 5
+                },
+            ]
+        );
+        assert_eq!(
+            report.to_string().lines().next().unwrap(),
+            "cell_id 5 expects 2 nodes but has 3"
+        );
+    }
+}
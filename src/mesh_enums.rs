@@ -1,10 +1,121 @@
 use derive_more::Display;
 
+use crate::mesh_errors::MeshError;
+use crate::node::Node;
+
 #[derive(Display)]
 pub enum MeshFormat {
     Mail,
 }
 
+// what to do with the cells referencing removed nodes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeRemovalPolicy {
+    // nodes referenced by a cell are not removed, an error is returned
+    Refuse,
+    // cells referencing a removed node are removed too
+    Cascade,
+}
+
+// renaming of an appended node, cell or group whose name is already used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameClashPolicy {
+    Prefix(String),
+    Suffix(String),
+    // the name clash is an error
+    Error,
+}
+
+// what to do with appended groups whose name is already used
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupMergePolicy {
+    // groups with the same name are merged
+    Union,
+    // appended groups are renamed with the name clash policy
+    Separate,
+}
+
+// node renumbering algorithms
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenumberingAlgorithm {
+    // reduces the bandwidth of the node adjacency graph
+    ReverseCuthillMcKee,
+    // reduces the profile of the node adjacency graph
+    Sloan,
+}
+
+// quality measures of cells, see `CellQuality`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QualityMetric {
+    AspectRatio,
+    ScaledJacobian,
+    Skewness,
+    Warpage,
+    MinDihedralAngle,
+    MaxDihedralAngle,
+}
+
+// defects making a cell unusable by a solver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DefectKind {
+    // node ids used more than once by the cell
+    RepeatedNodes(Vec<usize>),
+    // the cell is inverted, or folded, at a corner or an integration point
+    NegativeJacobian,
+    // the length, area or volume of the cell is zero
+    ZeroMeasure,
+}
+
+// reference used to orient the normals of surface cells
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrientationReference {
+    // normals point away from the point
+    AwayFromPoint(Node),
+    // normals point along the vector
+    Along(Node),
+}
+
+// broken invariant of a mesh, see `Mesh::validate`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    // the number of nodes of the cell does not match its type
+    ConnectivitySize {
+        cell_id: usize,
+        expected: usize,
+        actual: usize,
+    },
+    // a cell uses a node id which is not in the mesh
+    UnknownNode { cell_id: usize, node_id: usize },
+    // a group of nodes contains a node id which is not in the mesh
+    UnknownNodeInGroup { group: String, node_id: usize },
+    // a group of cells contains a cell id which is not in the mesh
+    UnknownCellInGroup { group: String, cell_id: usize },
+    // the same name is stored for several nodes
+    DuplicateNodeName(String),
+    // the same name is stored for several cells
+    DuplicateCellName(String),
+    // the name of the node cannot be written in the format
+    InvalidNodeName { node_id: usize, name: String },
+    // the name of the cell cannot be written in the format
+    InvalidCellName { cell_id: usize, name: String },
+    // the name of the group of nodes cannot be written in the format
+    InvalidNodeGroupName(String),
+    // the name of the group of cells cannot be written in the format
+    InvalidCellGroupName(String),
+    // the node is not used by any cell
+    OrphanNode(usize),
+    // the next node id is already used
+    NextNodeIdTooLow {
+        next_node_id: usize,
+        max_node_id: usize,
+    },
+    // the next cell id is already used
+    NextCellIdTooLow {
+        next_cell_id: usize,
+        max_cell_id: usize,
+    },
+}
+
 // / POI1 points )
 // / SEG2 / SEG3 / SEG4 segments )
 // / TRIA3 / TRIA6 / TRIA7 triangles )
@@ -13,7 +124,7 @@ pub enum MeshFormat {
 // / PENTA6 / PENTA15 / PENTA18 pentaèdres )
 // / TETRA4 / TETRA10 tétraèdres )
 // / PYRAM5 / PYRAM13 pyramides )
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Display)]
 pub enum CellType {
     POI1,
     SEG2,
@@ -37,7 +148,7 @@ pub enum CellType {
     PYRAM13,
 }
 impl CellType {
-    pub fn from_string(value: &str) -> Result<Self, &str> {
+    pub fn from_string(value: &str) -> Result<Self, MeshError> {
         match value {
             "POI1" => Ok(CellType::POI1),
             "SEG2" => Ok(CellType::SEG2),
@@ -59,7 +170,7 @@ impl CellType {
             "TETRA10" => Ok(CellType::TETRA10),
             "PYRAM5" => Ok(CellType::PYRAM5),
             "PYRAM13" => Ok(CellType::PYRAM13),
-            _ => Err("cell_type not implemented"),
+            _ => Err(MeshError::UnknownCellType(value.to_string())),
         }
     }
     pub fn get_nb_of_connectivities(&self) -> usize {
@@ -92,6 +203,16 @@ impl CellType {
 mod tests {
 
     use crate::mesh_enums::CellType;
+    use crate::mesh_errors::MeshError;
+
+    #[test]
+    fn cell_type_from_string_should_work() {
+        assert_eq!(CellType::from_string("HEXA20").unwrap(), CellType::HEXA20);
+        assert!(matches!(
+            CellType::from_string("HEXA21"),
+            Err(MeshError::UnknownCellType(name)) if name == "HEXA21"
+        ));
+    }
 
     #[test]
     fn cell_type_connectivity_nb_should_work() {
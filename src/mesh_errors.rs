@@ -0,0 +1,248 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::path::PathBuf;
+
+use crate::mesh_enums::CellType;
+use crate::node::Node;
+
+// entity referencing a node or a cell by its name
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReferencedBy {
+    Cell(String),
+    Group(String),
+}
+
+impl fmt::Display for ReferencedBy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReferencedBy::Cell(name) => write!(f, "cell {}", name),
+            ReferencedBy::Group(name) => write!(f, "group {}", name),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum MeshError {
+    // the mesh file could not be read or written
+    Io {
+        path: PathBuf,
+        source: io::Error,
+    },
+    // the mesh file does not follow the format syntax
+    Syntax {
+        // 1-based line and column of the offending input
+        line: usize,
+        column: usize,
+        // section being parsed, e.g. "COOR_3D" or "GROUP_MA APPUI"
+        section: Option<String>,
+        expected: String,
+    },
+    // a cell or a group references a node name which is not defined
+    UnknownNodeName {
+        name: String,
+        referenced_by: Option<ReferencedBy>,
+    },
+    // a group references a cell name which is not defined
+    UnknownCellName {
+        name: String,
+        referenced_by: Option<ReferencedBy>,
+    },
+    // a node name is defined more than once
+    DuplicateNodeName(String),
+    // a cell name is defined more than once
+    DuplicateCellName(String),
+    // a node name cannot be written in .mail files: letters followed by digits, 8 at most
+    InvalidNodeName(String),
+    // a cell name cannot be written in .mail files: letters followed by digits, 8 at most
+    InvalidCellName(String),
+    // a group name cannot be written in .mail files
+    InvalidGroupName(String),
+    // a prefix of the naming scheme is not made of letters
+    InvalidNamePrefix(String),
+    // a node id is not contained in mesh
+    UnknownNodeId(usize),
+    // a cell id is not contained in mesh
+    UnknownCellId(usize),
+    // a group of nodes is not contained in mesh
+    UnknownNodeGroup(String),
+    // a group of cells is not contained in mesh
+    UnknownCellGroup(String),
+    // a group of nodes with the same name already exists
+    DuplicateNodeGroup(String),
+    // a group of cells with the same name already exists
+    DuplicateCellGroup(String),
+    // a node cannot be removed because a cell references it
+    NodeInUse {
+        node_id: usize,
+        cell_id: usize,
+    },
+    // a cell type name is not supported
+    UnknownCellType(String),
+    // the number of nodes does not match the cell type
+    ConnectivitySize {
+        cell_type: CellType,
+        expected: usize,
+        actual: usize,
+        // name of the cell, when it is built from a mesh file
+        cell_name: Option<String>,
+    },
+    // a rotation axis or a mirror normal is too short to define a direction
+    DegenerateVector(Node),
+}
+
+impl fmt::Display for MeshError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshError::Io { path, source } => {
+                write!(f, "unable to access file {} : {}", path.display(), source)
+            }
+            MeshError::Syntax {
+                line,
+                column,
+                section,
+                expected,
+            } => {
+                write!(f, "syntax error at line {}, column {}", line, column)?;
+                if let Some(section) = section {
+                    write!(f, " in section {}", section)?;
+                }
+                write!(f, " : {}", expected)
+            }
+            MeshError::UnknownNodeName {
+                name,
+                referenced_by,
+            } => {
+                write!(f, "node {} is not defined", name)?;
+                if let Some(referenced_by) = referenced_by {
+                    write!(f, " (referenced by {})", referenced_by)?;
+                }
+                Ok(())
+            }
+            MeshError::UnknownCellName {
+                name,
+                referenced_by,
+            } => {
+                write!(f, "cell {} is not defined", name)?;
+                if let Some(referenced_by) = referenced_by {
+                    write!(f, " (referenced by {})", referenced_by)?;
+                }
+                Ok(())
+            }
+            MeshError::DuplicateNodeName(name) => {
+                write!(f, "node name {} is defined more than once", name)
+            }
+            MeshError::DuplicateCellName(name) => {
+                write!(f, "cell name {} is defined more than once", name)
+            }
+            MeshError::InvalidNodeName(name) => write!(f, "node name {} is not valid", name),
+            MeshError::InvalidCellName(name) => write!(f, "cell name {} is not valid", name),
+            MeshError::InvalidGroupName(name) => write!(f, "group name {} is not valid", name),
+            MeshError::InvalidNamePrefix(prefix) => {
+                write!(f, "name prefix {} is not made of letters", prefix)
+            }
+            MeshError::UnknownNodeId(node_id) => {
+                write!(f, "node_id {} not found in nodes", node_id)
+            }
+            MeshError::UnknownCellId(cell_id) => {
+                write!(f, "cell_id {} not found in cells", cell_id)
+            }
+            MeshError::UnknownNodeGroup(name) => write!(f, "group of nodes {} not found", name),
+            MeshError::UnknownCellGroup(name) => write!(f, "group of cells {} not found", name),
+            MeshError::DuplicateNodeGroup(name) => {
+                write!(f, "group of nodes {} already exists", name)
+            }
+            MeshError::DuplicateCellGroup(name) => {
+                write!(f, "group of cells {} already exists", name)
+            }
+            MeshError::NodeInUse { node_id, cell_id } => {
+                write!(f, "node_id {} is used by cell_id {}", node_id, cell_id)
+            }
+            MeshError::UnknownCellType(name) => write!(f, "cell type {} not implemented", name),
+            MeshError::ConnectivitySize {
+                cell_type,
+                expected,
+                actual,
+                cell_name,
+            } => {
+                write!(
+                    f,
+                    "{} cells expect {} nodes but {} were given",
+                    cell_type, expected, actual
+                )?;
+                if let Some(cell_name) = cell_name {
+                    write!(f, " (cell {})", cell_name)?;
+                }
+                Ok(())
+            }
+            MeshError::DegenerateVector(vector) => write!(
+                f,
+                "vector ({}, {}, {}) is too short to define a direction",
+                vector.x, vector.y, vector.z
+            ),
+        }
+    }
+}
+
+impl Error for MeshError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            MeshError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn mesh_error_display_should_work() {
+        let error = MeshError::Syntax {
+            line: 12,
+            column: 3,
+            section: Some("GROUP_MA APPUI".to_string()),
+            expected: "expected \"FINSF\"".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "syntax error at line 12, column 3 in section GROUP_MA APPUI : expected \"FINSF\""
+        );
+        let error = MeshError::UnknownNodeName {
+            name: "N12".to_string(),
+            referenced_by: Some(ReferencedBy::Cell("M5".to_string())),
+        };
+        assert_eq!(error.to_string(), "node N12 is not defined (referenced by cell M5)");
+        let error = MeshError::UnknownCellName {
+            name: "M5".to_string(),
+            referenced_by: Some(ReferencedBy::Group("APPUI".to_string())),
+        };
+        assert_eq!(error.to_string(), "cell M5 is not defined (referenced by group APPUI)");
+        let error = MeshError::ConnectivitySize {
+            cell_type: CellType::TRIA3,
+            expected: 3,
+            actual: 4,
+            cell_name: None,
+        };
+        assert_eq!(error.to_string(), "TRIA3 cells expect 3 nodes but 4 were given");
+        let error = MeshError::ConnectivitySize {
+            cell_type: CellType::SEG2,
+            expected: 2,
+            actual: 1,
+            cell_name: Some("M7".to_string()),
+        };
+        assert_eq!(error.to_string(), "SEG2 cells expect 2 nodes but 1 were given (cell M7)");
+    }
+
+    #[test]
+    fn mesh_error_should_chain_io_source() {
+        let error = MeshError::Io {
+            path: PathBuf::from("missing.mail"),
+            source: io::Error::new(io::ErrorKind::NotFound, "not found"),
+        };
+        assert!(error.source().is_some());
+        assert!(MeshError::DuplicateNodeName("N1".to_string()).source().is_none());
+    }
+}
@@ -4,9 +4,10 @@ use nom::{
     character::complete::{
         alpha1, alphanumeric1, digit1, line_ending, multispace0, multispace1, space0, space1
     },
-    combinator::{opt, recognize},
+    combinator::{cut, opt, recognize},
+    error::{ErrorKind, ParseError},
     multi::{many0, many1, many_m_n, separated_list0},
-    number::complete::float,
+    number::complete::double,
     sequence::{delimited, pair, preceded, tuple},
     IResult,
 };
@@ -18,7 +19,7 @@ use nom_supreme::{
 };
 
 use crate::{
-    lib::CellType,
+    lib::{CellType, MeshError},
     parsers::tokens::{CellProp, Group, GroupType, MailParseOutput, MailValue, NodeProp},
 };
 
@@ -27,26 +28,26 @@ fn node_or_cell_name(input: &str) -> IResult<&str, &str, ErrorTree<&str>> {
     Ok((input, name))
 }
 
-fn node_3d_coords(input: &str) -> IResult<&str, [f32; 3], ErrorTree<&str>> {
-    let (input, (_, x, _, y, _, z, _)) = tuple((
+// Parses the `dim` coordinates of a node, missing coordinates are set to zero
+fn node_coords(dim: usize, input: &str) -> IResult<&str, [f64; 3], ErrorTree<&str>> {
+    let (input, (_, first, others, _)) = tuple((
         space0,
-        float,
-        multispace1,
-        float,
-        multispace1,
-        float,
+        double,
+        many_m_n(dim - 1, dim - 1, preceded(multispace1, double)),
         space0,
     ))(input)?;
 
-    Ok((input, [x, y, z]))
+    let mut coords = [first, 0., 0.];
+    coords[1..dim].copy_from_slice(&others);
+    Ok((input, coords))
 }
 
-fn node_description(input: &str) -> IResult<&str, NodeProp, ErrorTree<&str>> {
+fn node_description(dim: usize, input: &str) -> IResult<&str, NodeProp<'_>, ErrorTree<&str>> {
     let (input, (_, name, _, [x, y, z], _)) = tuple((
         space0,
         node_or_cell_name,
         space0,
-        node_3d_coords,
+        |input| node_coords(dim, input),
         multispace0,
     ))(input)?;
     Ok((
@@ -60,7 +61,7 @@ fn node_description(input: &str) -> IResult<&str, NodeProp, ErrorTree<&str>> {
     ))
 }
 
-fn cell_description(cell_type: CellType, input: &str) -> IResult<&str, CellProp, ErrorTree<&str>> {
+fn cell_description(cell_type: CellType, input: &str) -> IResult<&str, CellProp<'_>, ErrorTree<&str>> {
     let nb_nodes: usize = cell_type.get_nb_of_connectivities();
     let (input, (name, node_names, _)) = tuple((
         node_or_cell_name,
@@ -77,7 +78,7 @@ fn cell_description(cell_type: CellType, input: &str) -> IResult<&str, CellProp,
     ))
 }
 
-fn group_description(group_type: GroupType, input: &str) -> IResult<&str, Group, ErrorTree<&str>> {
+fn group_description(group_type: GroupType, input: &str) -> IResult<&str, Group<'_>, ErrorTree<&str>> {
     let (input, (_, _, _, grp_name, _)) = tuple((
         space0,
         opt(tuple((tag_no_case("NOM"), space0, tag("=")))).context("optional NOM ="),
@@ -86,7 +87,7 @@ fn group_description(group_type: GroupType, input: &str) -> IResult<&str, Group,
         many1(alt((space1, recognize(comment_or_line_ending)))),
     ))(input)?;
     let (input, (elems_names, _, _, _)) = tuple((
-        separated_list0(space1, node_or_cell_name).context("elements names"),
+        separated_list0(multispace1, node_or_cell_name).context("elements names"),
         many0(comment_or_line_ending).context("optional space comment or line ending"),
         multispace0,
         end_section_tag,
@@ -109,23 +110,28 @@ fn start_title_section(input: &str) -> IResult<&str, &str, ErrorTree<&str>> {
     tag("TITRE")(input)
 }
 
-fn title_section(input: &str) -> IResult<&str, MailValue, ErrorTree<&str>> {
-    let (input, (_, _, _, _, title, _, _, _)) = tuple((
-        space0,
-        start_title_section,
+fn title_section(input: &str) -> IResult<&str, MailValue<'_>, ErrorTree<&str>> {
+    let (input, _) = tuple((space0, start_title_section))(input)?;
+    // once the section is started, errors are reported where they occur
+    let (input, (_, _, title, _, _, _)) = cut(tuple((
         multispace0,
         many0(comment_or_line_ending),
         is_not("\n").context("not end of line"),
         many0(comment_or_line_ending),
         multispace0,
         end_section_tag,
-    ))(input)?;
+    )))(input)?;
 
     Ok((input, MailValue::Title(title)))
 }
 
-fn start_3d_node_section(input: &str) -> IResult<&str, &str, ErrorTree<&str>> {
-    tag("COOR_3D")(input)
+fn start_node_section(input: &str) -> IResult<&str, usize, ErrorTree<&str>> {
+    let (input, dim) = alt((tag("COOR_1D"), tag("COOR_2D"), tag("COOR_3D")))(input)?;
+    match dim {
+        "COOR_1D" => Ok((input, 1)),
+        "COOR_2D" => Ok((input, 2)),
+        _ => Ok((input, 3)),
+    }
 }
 
 fn start_gno_section(input: &str) -> IResult<&str, GroupType, ErrorTree<&str>> {
@@ -144,18 +150,20 @@ fn start_gma_section(input: &str) -> IResult<&str, GroupType, ErrorTree<&str>> {
 //  N3        3.00000000000000E+00  4.00000000000000E+00  1.50000000000000E+00
 //  N4        4.00000000000000E+00  4.00000000000000E+00  1.50000000000000E+00
 // FINSF
-fn node_3d_section(input: &str) -> IResult<&str, MailValue, ErrorTree<&str>> {
-    let (input, (_, _, nodes, _, _)) = tuple((
-        tuple((space0, start_3d_node_section, space0)),
+// COOR_1D and COOR_2D sections only give the first coordinates of each node
+fn node_section(input: &str) -> IResult<&str, MailValue<'_>, ErrorTree<&str>> {
+    let (input, (_, dim, _)) = tuple((space0, start_node_section, space0))(input)?;
+    let (input, (_, nodes, _, _)) = cut(tuple((
         many1(comment_or_line_ending),
-        many0(preceded(multispace0, node_description)),
+        many0(preceded(multispace0, |input| node_description(dim, input))),
         many0(comment_or_line_ending),
         end_section_tag,
-    ))(input)?;
-    Ok((input, MailValue::NodeElts(nodes)))
+    )))(input)?;
+    Ok((input, MailValue::NodeElts(dim, nodes)))
 }
 
 fn cell_type_tag(input: &str) -> IResult<&str, CellType, ErrorTree<&str>> {
+    let start = input;
     let (input, cell_type_str) = alt((
         tag("POI1"),
         tag("SEG2"),
@@ -178,23 +186,22 @@ fn cell_type_tag(input: &str) -> IResult<&str, CellType, ErrorTree<&str>> {
         tag("PYRAM5"),
         tag("PYRAM13"),
     ))(input)?;
-    let cell_type_resu = CellType::from_string(cell_type_str);
-    if let Ok(cell_type) = cell_type_resu {
-        Ok((input, cell_type))
-    } else {
-        panic!("{} not implemented", cell_type_str);
+    match CellType::from_string(cell_type_str) {
+        Ok(cell_type) => Ok((input, cell_type)),
+        Err(_) => Err(nom::Err::Error(ErrorTree::from_error_kind(start, ErrorKind::Tag))),
     }
 }
 
-fn cell_section(input: &str) -> IResult<&str, MailValue, ErrorTree<&str>> {
+fn cell_section(input: &str) -> IResult<&str, MailValue<'_>, ErrorTree<&str>> {
     let (input, (_, cell_type, _)) = tuple((space0, cell_type_tag, space0))(input)?;
-    let (input, (cells, _, _)) = tuple((
+    let (input, (_, cells, _, _)) = cut(tuple((
+        many0(comment_or_line_ending),
         many0(preceded(multispace0, |input| {
             cell_description(cell_type.clone(), input)
         })),
         many0(comment_or_line_ending),
         end_section_tag,
-    ))(input)?;
+    )))(input)?;
     Ok((input, MailValue::Cells(cells)))
 }
 
@@ -203,18 +210,18 @@ fn group_name(input: &str) -> IResult<&str, &str, ErrorTree<&str>> {
     Ok((input, name))
 }
 
-fn group_section(input: &str) -> IResult<&str, MailValue, ErrorTree<&str>> {
-    let (input, (_, group_type, _, _, _)) = tuple((
-        space0,
-        alt((start_gno_section, start_gma_section)),
+fn group_section(input: &str) -> IResult<&str, MailValue<'_>, ErrorTree<&str>> {
+    let (input, (_, group_type)) =
+        tuple((space0, alt((start_gno_section, start_gma_section))))(input)?;
+
+    let (input, (_, _, _, group)) = cut(tuple((
         multispace0,
         many0(comment_or_line_ending),
         multispace0,
-    ))(input)?;
-
-    let (input, group) = preceded(multispace0, |input| {
-        group_description(group_type.clone(), input)
-    })(input)?;
+        preceded(multispace0, |input| {
+            group_description(group_type.clone(), input)
+        }),
+    )))(input)?;
     Ok((input, MailValue::Group(group)))
 }
 
@@ -234,14 +241,14 @@ fn useless_line(input: &str) -> IResult<&str, (), ErrorTree<&str>> {
     Ok((input, ()))
 }
 
-fn mail_intermediate_parser(input: &str) -> IResult<&str, MailParseOutput, ErrorTree<&str>> {
+fn mail_intermediate_parser(input: &str) -> IResult<&str, MailParseOutput<'_>, ErrorTree<&str>> {
     let (input, parsed) = delimited(
         many0(useless_line),
         separated_list0(
             many1(useless_line),
             tuple((
                 multispace0,
-                alt((title_section, node_3d_section, cell_section, group_section)),
+                alt((title_section, node_section, cell_section, group_section)),
             )),
         ),
         tuple((many0(useless_line), multispace0, tag("FIN"), multispace0, many0(useless_line))),
@@ -250,7 +257,8 @@ fn mail_intermediate_parser(input: &str) -> IResult<&str, MailParseOutput, Error
     let output = parsed.iter().fold(
         MailParseOutput::new(),
         |mut acc: MailParseOutput, (_, item)| match item {
-            MailValue::NodeElts(nodes) => {
+            MailValue::NodeElts(dim, nodes) => {
+                acc.dim = Some(acc.dim.map_or(*dim, |acc_dim| acc_dim.max(*dim)));
                 acc.nodes.extend(nodes.to_owned());
                 acc
             }
@@ -275,6 +283,90 @@ pub fn mail_parser(input: &str) -> Result<MailParseOutput<'_>, ErrorTree<&str>>
     final_parser(mail_intermediate_parser)(input)
 }
 
+// Returns the deepest error of the tree, i.e. the one which went the furthest in the input
+fn deepest_error<'a>(error: &ErrorTree<&'a str>) -> (&'a str, String) {
+    match error {
+        ErrorTree::Base { location, kind } => (location, kind.to_string()),
+        ErrorTree::Stack { base, .. } => deepest_error(base),
+        ErrorTree::Alt(siblings) => {
+            let errors: Vec<(&str, String)> = siblings.iter().map(deepest_error).collect();
+            let min_len = errors.iter().map(|(location, _)| location.len()).min();
+            let expected = errors
+                .iter()
+                .filter(|(location, _)| Some(location.len()) == min_len)
+                .map(|(_, expected)| expected.clone())
+                .collect::<Vec<String>>()
+                .join(" or ");
+            let location = errors
+                .iter()
+                .find(|(location, _)| Some(location.len()) == min_len)
+                .map_or("", |(location, _)| location);
+            (location, expected)
+        }
+    }
+}
+
+// Returns the section header which is opened at the given offset, e.g. "COOR_3D" or "GROUP_MA APPUI"
+fn section_at(input: &str, offset: usize) -> Option<String> {
+    let mut section: Option<String> = None;
+    let mut waiting_group_name = false;
+    let mut line_start = 0;
+    for line in input.split_inclusive('\n') {
+        if line_start > offset {
+            break;
+        }
+        let end = offset - line_start;
+        line_start += line.len();
+        let content = line.split('%').next().unwrap_or("");
+        // only tokens starting before the offset are taken into account
+        let tokens = content
+            .split_whitespace()
+            .filter(|token| token.as_ptr() as usize - content.as_ptr() as usize <= end);
+        for token in tokens {
+            if waiting_group_name {
+                if !token.eq_ignore_ascii_case("NOM") && token != "=" {
+                    section = section.map(|keyword| format!("{} {}", keyword, token));
+                    waiting_group_name = false;
+                }
+                continue;
+            }
+            match token {
+                "FINSF" | "FIN" => section = None,
+                "TITRE" | "COOR_1D" | "COOR_2D" | "COOR_3D" => section = Some(token.to_string()),
+                "GROUP_NO" | "GROUP_MA" => {
+                    section = Some(token.to_string());
+                    waiting_group_name = true;
+                }
+                _ if section.is_none() && CellType::from_string(token).is_ok() => {
+                    section = Some(token.to_string())
+                }
+                _ => (),
+            }
+        }
+    }
+    section
+}
+
+/// Converts a .mail parser error into a syntax error located in the input
+pub fn mail_syntax_error(input: &str, error: &ErrorTree<&str>) -> MeshError {
+    let (location, expected) = deepest_error(error);
+    // points to the first non blank character of the offending input
+    let location = location.trim_start_matches([' ', '\t']);
+    let offset = input.len() - location.len();
+    let before = &input[..offset];
+    let line = before.matches('\n').count() + 1;
+    let column = match before.rfind('\n') {
+        Some(line_start) => before[line_start + 1..].chars().count() + 1,
+        None => before.chars().count() + 1,
+    };
+    MeshError::Syntax {
+        line,
+        column,
+        section: section_at(input, offset),
+        expected,
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -283,12 +375,12 @@ mod tests {
 
     #[test]
     fn node_description_parser_should_work() {
-        assert_debug_snapshot!("node_descr_missing_first_letter", node_description("23"));
-        assert_debug_snapshot!("node_descr_missing_coords", node_description("N12"));
-        assert_debug_snapshot!("node_descr_ok", node_description("N12 1.2  23.3 233\n"));
+        assert_debug_snapshot!("node_descr_missing_first_letter", node_description(3, "23"));
+        assert_debug_snapshot!("node_descr_missing_coords", node_description(3, "N12"));
+        assert_debug_snapshot!("node_descr_ok", node_description(3, "N12 1.2  23.3 233\n"));
         assert_debug_snapshot!(
             "node_descr_ok_with_extra_space",
-            node_description("  N12 1.2  23.3 233\n")
+            node_description(3, "  N12 1.2  23.3 233\n")
         );
     }
 
@@ -301,18 +393,34 @@ mod tests {
     }
     #[test]
     fn should_parse_node_3d_coords() {
-        assert_debug_snapshot!("node_3d_coords_ok_1", node_3d_coords("1 2 3"));
-        assert_debug_snapshot!("node_3d_coords_ok_2", node_3d_coords("   1  2 3 "));
-        assert_debug_snapshot!("node_3d_coords_ok_3", node_3d_coords("   1.0  2 3.01 "));
+        assert_debug_snapshot!("node_3d_coords_ok_1", node_coords(3, "1 2 3"));
+        assert_debug_snapshot!("node_3d_coords_ok_2", node_coords(3, "   1  2 3 "));
+        assert_debug_snapshot!("node_3d_coords_ok_3", node_coords(3, "   1.0  2 3.01 "));
         assert_debug_snapshot!(
             "node_3d_coords_ok_4",
-            node_3d_coords("   1.0e1  2E+1 3.01E+00 ")
+            node_coords(3, "   1.0e1  2E+1 3.01E+00 ")
         );
         assert_debug_snapshot!(
             "node_3d_coords_nook_period",
-            node_3d_coords(" 1,2  23.3 233")
+            node_coords(3, " 1,2  23.3 233")
         );
-        assert_debug_snapshot!("node_3d_coords_ok_5", node_3d_coords("   1\n  2 3 "));
+        assert_debug_snapshot!("node_3d_coords_ok_5", node_coords(3, "   1\n  2 3 "));
+    }
+
+    #[test]
+    fn node_coords_should_keep_full_precision() {
+        let (_, coords) = node_coords(3, "1.00000000000000E+00 0.123456789012345678 -7.77777777777777E-10").unwrap();
+        assert_eq!(coords[0].to_bits(), 1.0_f64.to_bits());
+        assert_eq!(coords[1].to_bits(), 0.12345678901234568_f64.to_bits());
+        assert_eq!(coords[2].to_bits(), (-7.77777777777777E-10_f64).to_bits());
+    }
+
+    #[test]
+    fn should_parse_node_1d_and_2d_coords() {
+        assert_debug_snapshot!("node_1d_coords_ok_1", node_coords(1, " 1.5 "));
+        assert_debug_snapshot!("node_2d_coords_ok_1", node_coords(2, " 1.5  -2.0E+00"));
+        assert_debug_snapshot!("node_2d_coords_ok_2", node_coords(2, "1.5 2 3"));
+        assert_debug_snapshot!("node_2d_coords_nook_missing", node_coords(2, "1.5"));
     }
 
     #[test]
@@ -337,20 +445,27 @@ mod tests {
 
     #[test]
     fn start_node_section_parser_should_work() {
-        assert_debug_snapshot!("start_section_ok", start_3d_node_section("COOR_3D"));
-        assert_debug_snapshot!("start_section_nook", start_3d_node_section("COORD3D"));
+        assert_debug_snapshot!("start_section_ok", start_node_section("COOR_3D"));
+        assert_debug_snapshot!("start_section_nook", start_node_section("COORD3D"));
+        assert_debug_snapshot!("start_section_1d_ok", start_node_section("COOR_1D"));
+        assert_debug_snapshot!("start_section_2d_ok", start_node_section("COOR_2D"));
     }
 
     #[test]
-    fn node_3d_section_parser_should_work() {
+    fn node_section_parser_should_work() {
         assert_debug_snapshot!(
             "node_section_1",
-            node_3d_section("COOR_3D  \n\nN1 2  3.0 4\nFINSF")
+            node_section("COOR_3D  \n\nN1 2  3.0 4\nFINSF")
         );
         assert_debug_snapshot!(
             "node_section_2",
-            node_3d_section("COOR_3D\nN1 2  3.0 4\nN2 3  4 4\nFINSF")
+            node_section("COOR_3D\nN1 2  3.0 4\nN2 3  4 4\nFINSF")
+        );
+        assert_debug_snapshot!(
+            "node_section_2d",
+            node_section("COOR_2D\nN1 2  3.0\nN2 3  4\nFINSF")
         );
+        assert_debug_snapshot!("node_section_1d", node_section("COOR_1D\nN1 2\nN2 3\nFINSF"));
     }
 
     #[test]
@@ -474,6 +589,43 @@ mod tests {
         assert_debug_snapshot!("useless_line_ok_5", useless_line(" % ble\n"));
     }
 
+    fn syntax_error(input: &str) -> MeshError {
+        mail_syntax_error(input, &mail_parser(input).unwrap_err())
+    }
+
+    #[test]
+    fn mail_syntax_error_should_locate_bad_node() {
+        let input = "TITRE\nmy title\nFINSF\nCOOR_3D\nN1 1. 2. 3.\nN2 1. 2,0 3.\nFINSF\nFIN";
+        assert_debug_snapshot!("mail_syntax_error_node", syntax_error(input));
+    }
+
+    #[test]
+    fn mail_syntax_error_should_locate_bad_group() {
+        let input = "COOR_3D\nN1 1. 2. 3.\nFINSF\nGROUP_NO\nNOM = BORD\nN1 N2\n  -N3\nFINSF\nFIN";
+        assert_debug_snapshot!("mail_syntax_error_group", syntax_error(input));
+    }
+
+    #[test]
+    fn mail_syntax_error_should_locate_bad_cell() {
+        let input = "COOR_3D\nN1 1. 2. 3.\nFINSF\n%comment\n SEG2 % comment\n M1 N1 N2\n M2 N1\nFINSF\nFIN";
+        assert_debug_snapshot!("mail_syntax_error_cell", syntax_error(input));
+    }
+
+    #[test]
+    fn mail_syntax_error_should_locate_unknown_section() {
+        let input = "COOR_3D\nN1 1. 2. 3.\nFINSF\nCOOR_4D\nN2 1. 2. 3.\nFINSF\nFIN";
+        assert_debug_snapshot!("mail_syntax_error_unknown_section", syntax_error(input));
+    }
+
+    #[test]
+    fn section_at_should_work() {
+        let input = "TITRE\nt\nFINSF\n GROUP_MA NOM = APPUI\n M1\nFINSF\n GROUP_NO\n BORD\n N1\n";
+        assert_eq!(section_at(input, 2), Some("TITRE".to_string()));
+        assert_eq!(section_at(input, 14), None);
+        assert_eq!(section_at(input, 35), Some("GROUP_MA APPUI".to_string()));
+        assert_eq!(section_at(input, input.len() - 2), Some("GROUP_NO BORD".to_string()));
+    }
+
     #[test]
     fn mail_final_parser_should_work() {
         assert_debug_snapshot!(
@@ -8,7 +8,8 @@ pub enum GroupType {
 
 #[derive(Debug, PartialEq)]
 pub enum MailValue<'a> {
-    NodeElts(Vec<NodeProp<'a>>),
+    // spatial dimension of the section and its nodes
+    NodeElts(usize, Vec<NodeProp<'a>>),
     Cells(Vec<CellProp<'a>>),
     Group(Group<'a>),
     Title(&'a str),
@@ -16,6 +17,8 @@ pub enum MailValue<'a> {
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct MailParseOutput<'a> {
+    // highest spatial dimension declared by the node sections (COOR_1D, COOR_2D or COOR_3D)
+    pub dim: Option<usize>,
     pub nodes: Vec<NodeProp<'a>>,
     pub cells: Vec<CellProp<'a>>,
     pub groups: Vec<Group<'a>>,
@@ -24,6 +27,7 @@ pub struct MailParseOutput<'a> {
 impl MailParseOutput<'_> {
     pub fn new() -> Self {
         MailParseOutput {
+            dim: None,
             nodes: vec![],
             cells: vec![],
             groups: vec![],
@@ -40,9 +44,9 @@ impl Default for MailParseOutput<'_> {
 #[derive(Debug, Clone, PartialEq)]
 pub struct NodeProp<'a> {
     pub name: Option<&'a str>,
-    pub x: f32,
-    pub y: f32,
-    pub z: f32,
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
 }
 
 #[derive(Debug, Clone, PartialEq)]
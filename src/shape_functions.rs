@@ -0,0 +1,615 @@
+use crate::mesh_enums::CellType;
+
+// Shape functions of the Code_aster reference elements.
+// They are built from a polynomial (rational for pyramids) basis spanning the same
+// space as the element, so that the functions are 1 at their node and 0 at the others.
+// Gradients are derivatives with respect to the reference coordinates.
+
+// Basis term `s^a t^b z^c / (1 - z)^p`, with (s, t) = (x, y), or (x + y, x - y) for
+// pyramids whose base is a diamond in the reference element
+type Term = [i32; 4];
+
+const SEG2_BASIS: [Term; 2] = [[0, 0, 0, 0], [1, 0, 0, 0]];
+const SEG3_BASIS: [Term; 3] = [[0, 0, 0, 0], [1, 0, 0, 0], [2, 0, 0, 0]];
+const SEG4_BASIS: [Term; 4] = [[0, 0, 0, 0], [1, 0, 0, 0], [2, 0, 0, 0], [3, 0, 0, 0]];
+const TRIA3_BASIS: [Term; 3] = [[0, 0, 0, 0], [1, 0, 0, 0], [0, 1, 0, 0]];
+const TRIA6_BASIS: [Term; 6] = [
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [2, 0, 0, 0],
+    [1, 1, 0, 0],
+    [0, 2, 0, 0],
+];
+const QUAD4_BASIS: [Term; 4] = [[0, 0, 0, 0], [1, 0, 0, 0], [0, 1, 0, 0], [1, 1, 0, 0]];
+const QUAD8_BASIS: [Term; 8] = [
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [1, 1, 0, 0],
+    [2, 0, 0, 0],
+    [0, 2, 0, 0],
+    [2, 1, 0, 0],
+    [1, 2, 0, 0],
+];
+const HEXA8_BASIS: [Term; 8] = [
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [0, 0, 1, 0],
+    [1, 1, 0, 0],
+    [0, 1, 1, 0],
+    [1, 0, 1, 0],
+    [1, 1, 1, 0],
+];
+// serendipity space
+const HEXA20_BASIS: [Term; 20] = [
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [0, 0, 1, 0],
+    [1, 1, 0, 0],
+    [0, 1, 1, 0],
+    [1, 0, 1, 0],
+    [1, 1, 1, 0],
+    [2, 0, 0, 0],
+    [0, 2, 0, 0],
+    [0, 0, 2, 0],
+    [2, 1, 0, 0],
+    [2, 0, 1, 0],
+    [1, 2, 0, 0],
+    [0, 2, 1, 0],
+    [1, 0, 2, 0],
+    [0, 1, 2, 0],
+    [2, 1, 1, 0],
+    [1, 2, 1, 0],
+    [1, 1, 2, 0],
+];
+const TETRA4_BASIS: [Term; 4] = [[0, 0, 0, 0], [1, 0, 0, 0], [0, 1, 0, 0], [0, 0, 1, 0]];
+const TETRA10_BASIS: [Term; 10] = [
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [0, 0, 1, 0],
+    [2, 0, 0, 0],
+    [0, 2, 0, 0],
+    [0, 0, 2, 0],
+    [1, 1, 0, 0],
+    [0, 1, 1, 0],
+    [1, 0, 1, 0],
+];
+// triangle (y, z) times segment x
+const PENTA6_BASIS: [Term; 6] = [
+    [0, 0, 0, 0],
+    [0, 1, 0, 0],
+    [0, 0, 1, 0],
+    [1, 0, 0, 0],
+    [1, 1, 0, 0],
+    [1, 0, 1, 0],
+];
+const PENTA15_BASIS: [Term; 15] = [
+    [0, 0, 0, 0],
+    [0, 1, 0, 0],
+    [0, 0, 1, 0],
+    [0, 2, 0, 0],
+    [0, 1, 1, 0],
+    [0, 0, 2, 0],
+    [1, 0, 0, 0],
+    [1, 1, 0, 0],
+    [1, 0, 1, 0],
+    [1, 2, 0, 0],
+    [1, 1, 1, 0],
+    [1, 0, 2, 0],
+    [2, 0, 0, 0],
+    [2, 1, 0, 0],
+    [2, 0, 1, 0],
+];
+const PYRAM5_BASIS: [Term; 5] = [
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [0, 0, 1, 0],
+    [1, 1, 0, 1],
+];
+// Bedrosian's rational basis
+const PYRAM13_BASIS: [Term; 13] = [
+    [0, 0, 0, 0],
+    [1, 0, 0, 0],
+    [0, 1, 0, 0],
+    [0, 0, 1, 0],
+    [1, 1, 0, 1],
+    [2, 0, 0, 0],
+    [0, 2, 0, 0],
+    [0, 0, 2, 0],
+    [1, 0, 1, 0],
+    [0, 1, 1, 0],
+    [2, 1, 0, 1],
+    [1, 2, 0, 1],
+    [1, 1, 1, 1],
+];
+
+// basis function, sum of terms with their coefficient
+type Polynomial = Vec<(f64, Term)>;
+
+fn monomials(terms: &[Term]) -> Vec<Polynomial> {
+    terms.iter().map(|term| vec![(1., *term)]).collect()
+}
+
+// tensor product bases x^a y^b z^c with a, b, c <= 2
+fn tensor_basis(dim: usize) -> Vec<Polynomial> {
+    let mut terms = vec![];
+    for c in 0..=if dim == 3 { 2 } else { 0 } {
+        for b in 0..=2 {
+            for a in 0..=2 {
+                terms.push([a, b, c, 0]);
+            }
+        }
+    }
+    monomials(&terms)
+}
+
+fn basis(cell_type: &CellType) -> Vec<Polynomial> {
+    match cell_type {
+        CellType::POI1 => monomials(&[[0, 0, 0, 0]]),
+        CellType::SEG2 => monomials(&SEG2_BASIS),
+        CellType::SEG3 => monomials(&SEG3_BASIS),
+        CellType::SEG4 => monomials(&SEG4_BASIS),
+        CellType::TRIA3 => monomials(&TRIA3_BASIS),
+        CellType::TRIA6 => monomials(&TRIA6_BASIS),
+        CellType::TRIA7 => {
+            // the bubble xy(1 - x - y) spans the same space as x²y + xy² with TRIA6
+            let mut basis = monomials(&TRIA6_BASIS);
+            basis.push(vec![(1., [2, 1, 0, 0]), (1., [1, 2, 0, 0])]);
+            basis
+        }
+        CellType::QUAD4 => monomials(&QUAD4_BASIS),
+        CellType::QUAD8 => monomials(&QUAD8_BASIS),
+        CellType::QUAD9 => tensor_basis(2),
+        CellType::HEXA8 => monomials(&HEXA8_BASIS),
+        CellType::HEXA20 => monomials(&HEXA20_BASIS),
+        CellType::HEXA27 => tensor_basis(3),
+        CellType::PENTA6 => monomials(&PENTA6_BASIS),
+        CellType::PENTA15 => monomials(&PENTA15_BASIS),
+        CellType::PENTA18 => {
+            let mut basis = monomials(&PENTA15_BASIS);
+            basis.extend(monomials(&[[2, 2, 0, 0], [2, 1, 1, 0], [2, 0, 2, 0]]));
+            basis
+        }
+        CellType::TETRA4 => monomials(&TETRA4_BASIS),
+        CellType::TETRA10 => monomials(&TETRA10_BASIS),
+        CellType::PYRAM5 => monomials(&PYRAM5_BASIS),
+        CellType::PYRAM13 => monomials(&PYRAM13_BASIS),
+    }
+}
+
+// a^n, with 0 for negative powers which only appear multiplied by a zero exponent
+fn power(value: f64, n: i32) -> f64 {
+    if n < 0 {
+        0.
+    } else {
+        value.powi(n)
+    }
+}
+
+// value and gradient of a basis term, (s, t) are rotated for pyramids
+fn evaluate_term([a, b, c, p]: Term, rotated: bool, xi: [f64; 3]) -> (f64, [f64; 3]) {
+    let (s, t) = match rotated {
+        true => (xi[0] + xi[1], xi[0] - xi[1]),
+        false => (xi[0], xi[1]),
+    };
+    let z = xi[2];
+    // rational terms vanish at the apex of pyramids, where they are not differentiable
+    if p > 0 && (1. - z).abs() < 1e-12 {
+        return (0., [0.; 3]);
+    }
+    let w = if p == 0 { 1. } else { 1. / (1. - z).powi(p) };
+    let value = power(s, a) * power(t, b) * power(z, c) * w;
+    let ds = a as f64 * power(s, a - 1) * power(t, b) * power(z, c) * w;
+    let dt = b as f64 * power(s, a) * power(t, b - 1) * power(z, c) * w;
+    let dz =
+        c as f64 * power(s, a) * power(t, b) * power(z, c - 1) * w + p as f64 * value / (1. - z);
+    match rotated {
+        true => (value, [ds + dt, ds - dt, dz]),
+        false => (value, [ds, dt, dz]),
+    }
+}
+
+// Coordinates of the nodes in the reference element. Mid-edge nodes are in the middle
+// of their edge (at one third for SEG4), face and volume centers at the mean of the corners
+pub(crate) fn reference_coordinates(cell_type: &CellType) -> Vec<[f64; 3]> {
+    let corners: Vec<[f64; 3]> = match cell_type.linear_type() {
+        CellType::POI1 => vec![[0., 0., 0.]],
+        CellType::SEG2 => vec![[-1., 0., 0.], [1., 0., 0.]],
+        CellType::TRIA3 => vec![[0., 0., 0.], [1., 0., 0.], [0., 1., 0.]],
+        CellType::QUAD4 => vec![[-1., -1., 0.], [1., -1., 0.], [1., 1., 0.], [-1., 1., 0.]],
+        CellType::HEXA8 => vec![
+            [-1., -1., -1.],
+            [1., -1., -1.],
+            [1., 1., -1.],
+            [-1., 1., -1.],
+            [-1., -1., 1.],
+            [1., -1., 1.],
+            [1., 1., 1.],
+            [-1., 1., 1.],
+        ],
+        CellType::PENTA6 => vec![
+            [-1., 1., 0.],
+            [-1., 0., 1.],
+            [-1., 0., 0.],
+            [1., 1., 0.],
+            [1., 0., 1.],
+            [1., 0., 0.],
+        ],
+        CellType::TETRA4 => vec![[0., 1., 0.], [0., 0., 1.], [0., 0., 0.], [1., 0., 0.]],
+        _ => vec![
+            [1., 0., 0.],
+            [0., 1., 0.],
+            [-1., 0., 0.],
+            [0., -1., 0.],
+            [0., 0., 1.],
+        ],
+    };
+    if *cell_type == CellType::SEG4 {
+        return vec![
+            [-1., 0., 0.],
+            [1., 0., 0.],
+            [-1. / 3., 0., 0.],
+            [1. / 3., 0., 0.],
+        ];
+    }
+    let mean = |indices: &[usize], coords: &[[f64; 3]]| {
+        let mut point = [0.; 3];
+        for index in indices {
+            for k in 0..3 {
+                point[k] += coords[*index][k] / indices.len() as f64;
+            }
+        }
+        point
+    };
+    let nb_corners = corners.len();
+    let mut coords = corners;
+    coords.resize(cell_type.get_nb_of_connectivities(), [0.; 3]);
+    for edge in cell_type
+        .edges()
+        .iter()
+        .filter(|edge| edge.nodes.len() == 3)
+    {
+        coords[edge.nodes[2]] = mean(&edge.nodes[..2], &coords);
+    }
+    for face in cell_type.faces() {
+        if matches!(face.ty, CellType::TRIA7 | CellType::QUAD9) {
+            let corners = &face.nodes[..face.ty.get_nb_of_corner_nodes()];
+            coords[face.nodes[face.nodes.len() - 1]] = mean(corners, &coords);
+        }
+    }
+    if *cell_type == CellType::HEXA27 {
+        let corners: Vec<usize> = (0..nb_corners).collect();
+        coords[26] = mean(&corners, &coords);
+    }
+    coords
+}
+
+// inverse of a square matrix by Gauss-Jordan elimination, None if it is singular
+fn inverse(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = matrix.len();
+    let mut a: Vec<Vec<f64>> = matrix.to_vec();
+    let mut inv: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1. } else { 0. }).collect())
+        .collect();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+        if a[pivot][col].abs() < 1e-12 {
+            return None;
+        }
+        a.swap(col, pivot);
+        inv.swap(col, pivot);
+        let factor = a[col][col];
+        for j in 0..n {
+            a[col][j] /= factor;
+            inv[col][j] /= factor;
+        }
+        for row in 0..n {
+            if row != col && a[row][col] != 0. {
+                let factor = a[row][col];
+                for j in 0..n {
+                    a[row][j] -= factor * a[col][j];
+                    inv[row][j] -= factor * inv[col][j];
+                }
+            }
+        }
+    }
+    Some(inv)
+}
+
+// shape functions of a cell type
+pub(crate) struct ShapeFunctions {
+    basis: Vec<Polynomial>,
+    rotated: bool,
+    // coefficients of the shape functions in the basis, one column per node
+    coefficients: Vec<Vec<f64>>,
+}
+
+impl ShapeFunctions {
+    pub(crate) fn new(cell_type: &CellType) -> Self {
+        let basis = basis(cell_type);
+        let rotated = matches!(cell_type, CellType::PYRAM5 | CellType::PYRAM13);
+        let mut shape_functions = ShapeFunctions {
+            basis,
+            rotated,
+            coefficients: vec![],
+        };
+        // vandermonde matrix: basis functions at the nodes
+        let vandermonde: Vec<Vec<f64>> = reference_coordinates(cell_type)
+            .into_iter()
+            .map(|xi| shape_functions.evaluate_basis(xi).0)
+            .collect();
+        shape_functions.coefficients =
+            inverse(&vandermonde).expect("the basis of a reference element must be unisolvent");
+        shape_functions
+    }
+
+    fn evaluate_basis(&self, xi: [f64; 3]) -> (Vec<f64>, Vec<[f64; 3]>) {
+        let mut values = vec![];
+        let mut gradients = vec![];
+        for polynomial in self.basis.iter() {
+            let mut value = 0.;
+            let mut gradient = [0.; 3];
+            for (coefficient, term) in polynomial {
+                let (term_value, term_gradient) = evaluate_term(*term, self.rotated, xi);
+                value += coefficient * term_value;
+                for k in 0..3 {
+                    gradient[k] += coefficient * term_gradient[k];
+                }
+            }
+            values.push(value);
+            gradients.push(gradient);
+        }
+        (values, gradients)
+    }
+
+    // values of the shape functions at a point of the reference element
+    pub(crate) fn values(&self, xi: [f64; 3]) -> Vec<f64> {
+        let (basis_values, _) = self.evaluate_basis(xi);
+        (0..self.coefficients.len())
+            .map(|node| {
+                basis_values
+                    .iter()
+                    .zip(self.coefficients.iter())
+                    .map(|(value, row)| value * row[node])
+                    .sum()
+            })
+            .collect()
+    }
+
+    // gradients of the shape functions at a point of the reference element
+    pub(crate) fn gradients(&self, xi: [f64; 3]) -> Vec<[f64; 3]> {
+        let (_, basis_gradients) = self.evaluate_basis(xi);
+        (0..self.coefficients.len())
+            .map(|node| {
+                let mut gradient = [0.; 3];
+                for (basis_gradient, row) in basis_gradients.iter().zip(self.coefficients.iter()) {
+                    for k in 0..3 {
+                        gradient[k] += basis_gradient[k] * row[node];
+                    }
+                }
+                gradient
+            })
+            .collect()
+    }
+
+    // position of a point of the reference element in the cell of node coordinates `coords`
+    pub(crate) fn position(&self, coords: &[[f64; 3]], xi: [f64; 3]) -> [f64; 3] {
+        let mut point = [0.; 3];
+        for (value, node) in self.values(xi).iter().zip(coords.iter()) {
+            for k in 0..3 {
+                point[k] += value * node[k];
+            }
+        }
+        point
+    }
+
+    // derivatives of the position with respect to the reference coordinates,
+    // `jacobian[i][k]` is the derivative of coordinate k along reference axis i
+    pub(crate) fn jacobian(&self, coords: &[[f64; 3]], xi: [f64; 3]) -> [[f64; 3]; 3] {
+        let mut jacobian = [[0.; 3]; 3];
+        for (gradient, node) in self.gradients(xi).iter().zip(coords.iter()) {
+            for i in 0..3 {
+                for k in 0..3 {
+                    jacobian[i][k] += gradient[i] * node[k];
+                }
+            }
+        }
+        jacobian
+    }
+}
+
+// Gauss-Legendre points and weights on [-1, 1]
+const GAUSS_POINTS: [(f64, f64); 4] = [
+    (-0.8611363115940526, 0.34785484513745385),
+    (-0.3399810435848563, 0.6521451548625461),
+    (0.3399810435848563, 0.6521451548625461),
+    (0.8611363115940526, 0.34785484513745385),
+];
+
+// Gauss-Legendre points and weights on [0, 1]
+fn unit_gauss_points() -> impl Iterator<Item = (f64, f64)> {
+    GAUSS_POINTS.iter().map(|(x, w)| ((x + 1.) / 2., w / 2.))
+}
+
+// points of the triangle (0, 0), (1, 0), (0, 1), collapsed from a square
+fn triangle_points() -> Vec<([f64; 2], f64)> {
+    let mut points = vec![];
+    for (a, wa) in unit_gauss_points() {
+        for (b, wb) in unit_gauss_points() {
+            points.push(([a, b * (1. - a)], wa * wb * (1. - a)));
+        }
+    }
+    points
+}
+
+// Integration points and weights of the reference element, exact for polynomials of
+// degree 7 on segments, quadrangles and hexahedra. Simplices and pyramids use
+// collapsed (Duffy) rules
+pub(crate) fn integration_points(cell_type: &CellType) -> Vec<([f64; 3], f64)> {
+    let mut points = vec![];
+    match cell_type.linear_type() {
+        CellType::POI1 => points.push(([0., 0., 0.], 1.)),
+        CellType::SEG2 => {
+            for (x, w) in GAUSS_POINTS {
+                points.push(([x, 0., 0.], w));
+            }
+        }
+        CellType::TRIA3 => {
+            for ([x, y], w) in triangle_points() {
+                points.push(([x, y, 0.], w));
+            }
+        }
+        CellType::QUAD4 => {
+            for (x, wx) in GAUSS_POINTS {
+                for (y, wy) in GAUSS_POINTS {
+                    points.push(([x, y, 0.], wx * wy));
+                }
+            }
+        }
+        CellType::HEXA8 => {
+            for (x, wx) in GAUSS_POINTS {
+                for (y, wy) in GAUSS_POINTS {
+                    for (z, wz) in GAUSS_POINTS {
+                        points.push(([x, y, z], wx * wy * wz));
+                    }
+                }
+            }
+        }
+        CellType::PENTA6 => {
+            for (x, wx) in GAUSS_POINTS {
+                for ([y, z], w) in triangle_points() {
+                    points.push(([x, y, z], wx * w));
+                }
+            }
+        }
+        CellType::TETRA4 => {
+            for ([x, y], w) in triangle_points() {
+                for (c, wc) in unit_gauss_points() {
+                    let z = c * (1. - x - y);
+                    points.push(([x, y, z], w * wc * (1. - x - y)));
+                }
+            }
+        }
+        _ => {
+            // the diamond |x| + |y| <= 1 - z is the image of the square [-1, 1]²
+            for (z, wz) in unit_gauss_points() {
+                for (u, wu) in GAUSS_POINTS {
+                    for (v, wv) in GAUSS_POINTS {
+                        let (x, y) = ((u + v) / 2. * (1. - z), (u - v) / 2. * (1. - z));
+                        points.push(([x, y, z], wz * wu * wv * (1. - z).powi(2) / 2.));
+                    }
+                }
+            }
+        }
+    }
+    points
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    const ALL_TYPES: [CellType; 20] = [
+        CellType::POI1,
+        CellType::SEG2,
+        CellType::SEG3,
+        CellType::SEG4,
+        CellType::TRIA3,
+        CellType::TRIA6,
+        CellType::TRIA7,
+        CellType::QUAD4,
+        CellType::QUAD8,
+        CellType::QUAD9,
+        CellType::HEXA8,
+        CellType::HEXA20,
+        CellType::HEXA27,
+        CellType::PENTA6,
+        CellType::PENTA15,
+        CellType::PENTA18,
+        CellType::TETRA4,
+        CellType::TETRA10,
+        CellType::PYRAM5,
+        CellType::PYRAM13,
+    ];
+
+    // a point inside every reference element
+    const INNER_POINT: [f64; 3] = [0.15, 0.2, 0.25];
+
+    #[test]
+    fn shape_functions_should_interpolate_nodes() {
+        for cell_type in ALL_TYPES.iter() {
+            let shape_functions = ShapeFunctions::new(cell_type);
+            let coords = reference_coordinates(cell_type);
+            for (i, xi) in coords.iter().enumerate() {
+                for (j, value) in shape_functions.values(*xi).iter().enumerate() {
+                    let expected = if i == j { 1. } else { 0. };
+                    assert!((value - expected).abs() < 1e-10, "{} node {}", cell_type, i);
+                }
+            }
+            let sum: f64 = shape_functions.values(INNER_POINT).iter().sum();
+            assert!((sum - 1.).abs() < 1e-10, "{}", cell_type);
+        }
+    }
+
+    #[test]
+    fn shape_gradients_should_match_finite_differences() {
+        let h = 1e-6;
+        for cell_type in ALL_TYPES.iter() {
+            let shape_functions = ShapeFunctions::new(cell_type);
+            let gradients = shape_functions.gradients(INNER_POINT);
+            for axis in 0..cell_type.topological_dimension() {
+                let mut forward = INNER_POINT;
+                let mut backward = INNER_POINT;
+                forward[axis] += h;
+                backward[axis] -= h;
+                let values_forward = shape_functions.values(forward);
+                let values_backward = shape_functions.values(backward);
+                for node in 0..gradients.len() {
+                    let derivative = (values_forward[node] - values_backward[node]) / (2. * h);
+                    assert!(
+                        (gradients[node][axis] - derivative).abs() < 1e-6,
+                        "{} node {} axis {}",
+                        cell_type,
+                        node,
+                        axis
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn integration_points_should_give_reference_measures() {
+        let expected = [
+            (CellType::SEG2, 2.),
+            (CellType::TRIA3, 0.5),
+            (CellType::QUAD4, 4.),
+            (CellType::HEXA8, 8.),
+            (CellType::PENTA6, 1.),
+            (CellType::TETRA4, 1. / 6.),
+            (CellType::PYRAM5, 2. / 3.),
+        ];
+        for (cell_type, measure) in expected {
+            let sum: f64 = integration_points(&cell_type).iter().map(|(_, w)| w).sum();
+            assert!((sum - measure).abs() < 1e-12, "{}", cell_type);
+        }
+    }
+
+    #[test]
+    fn reference_coordinates_should_follow_code_aster() {
+        let coords = reference_coordinates(&CellType::HEXA20);
+        // N13 is in the middle of N1 N5
+        assert_eq!(coords[12], [-1., -1., 0.]);
+        let coords = reference_coordinates(&CellType::PENTA18);
+        assert_eq!(coords[15], [0., 0.5, 0.5]);
+        let coords = reference_coordinates(&CellType::PYRAM13);
+        assert_eq!(coords[9], [0.5, 0., 0.5]);
+        assert_eq!(reference_coordinates(&CellType::HEXA27)[26], [0., 0., 0.]);
+    }
+}
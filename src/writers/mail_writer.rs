@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::lib::{CellType, Mesh};
+
+// Number of node or cell names written on a single line
+const NAMES_PER_LINE: usize = 8;
+
+const TITLE: &str = "MESH WRITTEN BY MESHB";
+
+fn format_coordinate(value: f64) -> String {
+    // 17 significant digits are needed to read back the exact same f64.
+    // Rust writes exponents as `E0` or `E-3`, Code_aster as `E+00` or `E-03`
+    let formatted = format!("{:.16E}", value);
+    let coordinate = match formatted.split_once('E') {
+        Some((mantissa, exponent)) => match exponent.parse::<i32>() {
+            Ok(exponent) => {
+                let sign = if exponent < 0 { '-' } else { '+' };
+                format!("{}E{}{:02}", mantissa, sign, exponent.abs())
+            }
+            Err(_) => formatted,
+        },
+        None => formatted,
+    };
+    format!("{:>23}", coordinate)
+}
+
+fn names_lines(names: &[String]) -> String {
+    names
+        .chunks(NAMES_PER_LINE)
+        .map(|chunk| {
+            let line = chunk
+                .iter()
+                .map(|name| format!("{:<8}", name))
+                .collect::<Vec<String>>()
+                .join(" ");
+            format!(" {}", line.trim_end())
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn sorted_ids<T>(map: &HashMap<usize, T>) -> Vec<usize> {
+    let mut ids: Vec<usize> = map.keys().copied().collect();
+    ids.sort();
+    ids
+}
+
+fn title_section(output: &mut String) {
+    writeln!(output, " TITRE").unwrap();
+    writeln!(output, " {}", TITLE).unwrap();
+    writeln!(output, " FINSF").unwrap();
+    writeln!(output, " %").unwrap();
+}
+
+fn node_section(mesh: &Mesh, output: &mut String) {
+    if mesh.nodes.is_empty() {
+        return;
+    }
+    let names = mesh.node_names();
+    // coordinates beyond the mesh dimension are not written
+    let dim = mesh.dim.clamp(1, 3);
+    writeln!(output, " COOR_{}D", dim).unwrap();
+    for node_id in sorted_ids(&mesh.nodes) {
+        let node = &mesh.nodes[&node_id];
+        let name = &names[&node_id];
+        let coords = [node.x, node.y, node.z][..dim]
+            .iter()
+            .map(|coord| format_coordinate(*coord))
+            .collect::<Vec<String>>()
+            .join(" ");
+        writeln!(output, " {:<8} {}", name, coords).unwrap();
+    }
+    writeln!(output, " FINSF").unwrap();
+    writeln!(output, " %").unwrap();
+}
+
+fn cell_sections(mesh: &Mesh, output: &mut String) {
+    let node_names = mesh.node_names();
+    let cell_names = mesh.cell_names();
+
+    // one section per cell type, in order of first appearance
+    let mut sections: Vec<(CellType, Vec<usize>)> = vec![];
+    for cell_id in sorted_ids(&mesh.cells) {
+        let cell_type = &mesh.cells[&cell_id].ty;
+        match sections.iter_mut().find(|(ty, _)| ty == cell_type) {
+            Some((_, cell_ids)) => cell_ids.push(cell_id),
+            None => sections.push((cell_type.clone(), vec![cell_id])),
+        }
+    }
+
+    for (cell_type, cell_ids) in sections {
+        writeln!(output, " {}", cell_type).unwrap();
+        for cell_id in cell_ids {
+            let mut names = vec![cell_names[&cell_id].clone()];
+            names.extend(mesh.cells[&cell_id].co.iter().map(|node_id| {
+                match node_names.get(node_id) {
+                    Some(name) => name.clone(),
+                    None => mesh.naming.node_name(*node_id),
+                }
+            }));
+            writeln!(output, "{}", names_lines(&names)).unwrap();
+        }
+        writeln!(output, " FINSF").unwrap();
+        writeln!(output, " %").unwrap();
+    }
+}
+
+fn group_sections(
+    keyword: &str,
+    groups: &HashMap<Box<str>, Vec<usize>>,
+    names: &HashMap<usize, String>,
+    default_name: impl Fn(usize) -> String,
+    output: &mut String,
+) {
+    let mut group_names: Vec<&Box<str>> = groups.keys().collect();
+    group_names.sort();
+    for group_name in group_names {
+        let elems: Vec<String> = groups[group_name]
+            .iter()
+            .map(|id| match names.get(id) {
+                Some(name) => name.clone(),
+                None => default_name(*id),
+            })
+            .collect();
+        writeln!(output, " {}", keyword).unwrap();
+        writeln!(output, " {}", group_name).unwrap();
+        if !elems.is_empty() {
+            writeln!(output, "{}", names_lines(&elems)).unwrap();
+        }
+        writeln!(output, " FINSF").unwrap();
+        writeln!(output, " %").unwrap();
+    }
+}
+
+/// Dumps a mesh to the Code_aster .mail format.
+///
+/// Stored node and cell names are used when available, otherwise names are
+/// generated from ids (`N1`, `M1`, ...).
+pub fn mail_writer(mesh: &Mesh) -> String {
+    let mut output = String::new();
+    title_section(&mut output);
+    node_section(mesh, &mut output);
+    cell_sections(mesh, &mut output);
+    group_sections(
+        "GROUP_NO",
+        &mesh.gno,
+        &mesh.node_names(),
+        |node_id| mesh.naming.node_name(node_id),
+        &mut output,
+    );
+    group_sections(
+        "GROUP_MA",
+        &mesh.gma,
+        &mesh.cell_names(),
+        |cell_id| mesh.naming.cell_name(cell_id),
+        &mut output,
+    );
+    writeln!(output, " FIN").unwrap();
+    output
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::lib::mail_parser::mail_parser;
+    use insta::assert_snapshot;
+
+    const MAIL_CONTENT: &str = r#"
+ COOR_3D
+ N1        1.00000000000000E+00  4.00000000000000E+00  2.50000000000000E+00
+ N2        2.00000000000000E+00  4.00000000000000E+00  1.50000000000000E+00
+ N3        3.00000000000000E+00  4.00000000000000E+00  1.50000000000000E+00
+ N4        4.00000000000000E+00  4.00000000000000E+00  1.50000000000000E+00
+FINSF
+ POI1
+ M1       N1
+ M2       N3
+ FINSF
+ SEG2
+ M5       N2       N1
+ M6       N3       N2
+FINSF
+ GROUP_MA
+ APPUI
+ M1       M2
+ FINSF
+ GROUP_NO
+ NOEU_MO
+ N1       N2       N3       N4
+  FINSF
+ FIN
+"#;
+
+    #[test]
+    fn format_coordinate_should_work() {
+        assert_eq!(format_coordinate(1.), " 1.0000000000000000E+00");
+        assert_eq!(format_coordinate(-2.5e-1), "-2.5000000000000000E-01");
+        assert_eq!(format_coordinate(1.25e120), "1.2500000000000000E+120");
+        assert_eq!(format_coordinate(0.), " 0.0000000000000000E+00");
+    }
+
+    #[test]
+    fn format_coordinate_should_not_lose_precision() {
+        for value in [0.1, 1. / 3., -123456.78901234567, 1e-9 + 1., f64::MIN_POSITIVE] {
+            let formatted = format_coordinate(value);
+            assert_eq!(formatted.trim().parse::<f64>().unwrap().to_bits(), value.to_bits());
+        }
+    }
+
+    #[test]
+    fn names_lines_should_wrap_long_lists() {
+        let names: Vec<String> = (1..=10).map(|i| format!("N{}", i)).collect();
+        assert_eq!(
+            names_lines(&names),
+            " N1       N2       N3       N4       N5       N6       N7       N8\n N9       N10"
+        );
+    }
+
+    #[test]
+    fn mail_writer_should_work_with_generated_names() {
+        let mut mesh = Mesh::new();
+        mesh.nodes.insert(0, crate::lib::Node { x: 0., y: 0., z: 0. });
+        mesh.nodes.insert(1, crate::lib::Node { x: 1., y: -1.5, z: 0. });
+        mesh.next_node_id = 2;
+        mesh.add_a_cell(CellType::SEG2, &[0, 1]).unwrap();
+        mesh.create_cell_group("BEAM", &[0]).unwrap();
+        assert_snapshot!("mail_writer_generated_names", mail_writer(&mesh));
+    }
+
+    #[test]
+    fn mail_writer_should_write_the_mesh_dimension() {
+        let content = "COOR_2D\nN1 2. 3.\nN2 4. 5.\nFINSF\nSEG2\nM1 N1 N2\nFINSF\nFIN";
+        let mesh = Mesh::create_from_parser_output(mail_parser(content).unwrap()).unwrap();
+        let written = mail_writer(&mesh);
+        assert!(written.contains(" COOR_2D\n N1        2.0000000000000000E+00  3.0000000000000000E+00\n"));
+        let reread = Mesh::create_from_parser_output(mail_parser(&written).unwrap()).unwrap();
+        assert_eq!(reread.dim, 2);
+        assert_eq!(reread.nodes, mesh.nodes);
+    }
+
+    #[test]
+    fn mail_writer_should_keep_parsed_names() {
+        let mesh = Mesh::create_from_parser_output(mail_parser(MAIL_CONTENT).unwrap()).unwrap();
+        assert_snapshot!("mail_writer_parsed_names", mail_writer(&mesh));
+    }
+
+    #[test]
+    fn mail_writer_output_should_be_parsable() {
+        let mesh = Mesh::create_from_parser_output(mail_parser(MAIL_CONTENT).unwrap()).unwrap();
+        let content = mail_writer(&mesh);
+        let reread = Mesh::create_from_parser_output(mail_parser(&content).unwrap()).unwrap();
+        assert_eq!(reread.nodes, mesh.nodes);
+        assert_eq!(reread.cells, mesh.cells);
+        assert_eq!(reread.gma, mesh.gma);
+        assert_eq!(reread.gno, mesh.gno);
+    }
+}
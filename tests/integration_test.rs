@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use meshb::lib::{MeshFormat, Mesh, Node, MeshCell};
+use meshb::lib::{MeshFormat, Mesh, MeshError, Node, MeshCell};
 
 #[test]
 fn test_mesh_from_mail_file() {
@@ -17,4 +17,44 @@ fn test_mesh_from_mail_file() {
     assert_eq!(mesh.get_cell_co(3).unwrap(), vec![3]);
     assert_eq!(*(mesh.nodes.get(&3).unwrap()), Node{x: 4.00000000000000E+00, y: 4.00000000000000E+00, z: 1.50000000000000E+00, } );
     assert_eq!(*(mesh.cells.get(&3).unwrap()), MeshCell{ ty:meshb::lib::CellType::POI1, co: vec![3] } );
+    assert_eq!(mesh.get_node_group("NOEU_MO").unwrap(), &[0, 1, 2, 3]);
+    assert_eq!(mesh.get_cell_group("APPUI").unwrap(), &[0, 1, 2, 3]);
+    assert!(!mesh.gma.contains_key("NOEU_MO"));
+}
+
+#[test]
+fn test_mesh_written_to_mail_file_can_be_read_back() {
+    let mesh_file: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"), "tests", "resources", "mesh_1.mail"]
+        .iter()
+        .collect();
+    let mesh = Mesh::read_mesh(mesh_file, MeshFormat::Mail).unwrap();
+
+    let output_file = std::env::temp_dir().join("meshb_integration_mesh_1.mail");
+    assert!(mesh.write_mesh(output_file.clone(), MeshFormat::Mail).is_ok());
+
+    let reread = Mesh::read_mesh(output_file.clone(), MeshFormat::Mail).unwrap();
+    std::fs::remove_file(output_file).unwrap();
+    assert_eq!(reread.nodes, mesh.nodes);
+    for (node_id, node) in mesh.nodes.iter() {
+        let reread_node = &reread.nodes[node_id];
+        assert_eq!(
+            [reread_node.x.to_bits(), reread_node.y.to_bits(), reread_node.z.to_bits()],
+            [node.x.to_bits(), node.y.to_bits(), node.z.to_bits()]
+        );
+    }
+    assert_eq!(reread.cells, mesh.cells);
+    assert_eq!(reread.gno, mesh.gno);
+    assert_eq!(reread.gma, mesh.gma);
+}
+
+#[test]
+fn test_missing_mail_file_should_return_io_error() {
+    let mesh_file: PathBuf = [
+        env!("CARGO_MANIFEST_DIR"), "tests", "resources", "missing.mail"]
+        .iter()
+        .collect();
+
+    let mesh_res = Mesh::read_mesh(mesh_file.clone(), MeshFormat::Mail);
+    assert!(matches!(mesh_res, Err(MeshError::Io { path, .. }) if path == mesh_file));
 }